@@ -0,0 +1,73 @@
+//! Runtime event subscriptions shared by the kitties and proof of existence node RPCs.
+//!
+//! Each node maps the events of its own pallet to notifications and filters them, this crate reads
+//! the events of every new best block and streams the notifications to the subscriber.
+
+#![warn(missing_docs)]
+
+use std::sync::Arc;
+
+use codec::Decode;
+use frame_support::Parameter;
+use futures::{future, stream, FutureExt, StreamExt};
+use jsonrpsee::SubscriptionSink;
+use sc_client_api::{Backend, BlockchainEvents, StateBackend};
+use sc_rpc::SubscriptionTaskExecutor;
+use serde::Serialize;
+use sp_core::twox_128;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Member, NumberFor};
+
+/// Stream to `sink` the events of every new best block that `notification` maps to a
+/// notification, on a task named `name`.
+pub fn spawn_event_subscription<Block, Event, C, B, N, F>(
+	name: &'static str,
+	client: &C,
+	backend: Arc<B>,
+	executor: &SubscriptionTaskExecutor,
+	sink: SubscriptionSink,
+	notification: F,
+) where
+	Block: BlockT,
+	Event: Parameter + Member,
+	C: BlockchainEvents<Block>,
+	B: Backend<Block> + Send + Sync + 'static,
+	N: Serialize + Send + 'static,
+	F: Fn(Block::Hash, NumberFor<Block>, Event) -> Option<N> + Send + 'static,
+{
+	let stream = client
+		.import_notification_stream()
+		.filter(|block| future::ready(block.is_new_best))
+		.flat_map(move |block| {
+			let (hash, number) = (block.hash, *block.header.number());
+			let notifications: Vec<_> = block_events::<Block, Event, B>(&*backend, hash)
+				.into_iter()
+				.filter_map(|event| notification(hash, number, event))
+				.collect();
+			stream::iter(notifications)
+		})
+		.boxed();
+
+	let fut = async move {
+		sink.pipe_from_stream(stream).await;
+	};
+	executor.spawn(name, Some("rpc"), fut.boxed());
+}
+
+/// The events deposited in the block `hash`, or none if they cannot be read.
+pub fn block_events<Block, Event, B>(backend: &B, hash: Block::Hash) -> Vec<Event>
+where
+	Block: BlockT,
+	Event: Parameter + Member,
+	B: Backend<Block>,
+{
+	let key = [twox_128(b"System"), twox_128(b"Events")].concat();
+	backend
+		.state_at(hash)
+		.ok()
+		.and_then(|state| state.storage(&key).ok().flatten())
+		.and_then(|data| {
+			Vec::<frame_system::EventRecord<Event, Block::Hash>>::decode(&mut &data[..]).ok()
+		})
+		.map(|records| records.into_iter().map(|record| record.event).collect())
+		.unwrap_or_default()
+}
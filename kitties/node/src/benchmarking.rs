@@ -2,10 +2,13 @@
 //!
 //! Should only be used for benchmarking as it may break in other contexts.
 
-use crate::service::FullClient;
+use crate::{
+	chain_spec::{BENCHMARK_FIRST_KITTY_ON_SALE, BENCHMARK_KITTIES_ON_SALE, BENCHMARK_PARENTS},
+	service::FullClient,
+};
 
 use node_template_runtime as runtime;
-use runtime::{AccountId, Balance, BalancesCall, SystemCall};
+use runtime::{AccountId, Balance, BalancesCall, KittiesCall, SystemCall};
 use sc_cli::Result;
 use sc_client_api::BlockBackend;
 use sp_core::{Encode, Pair};
@@ -95,6 +98,127 @@ impl frame_benchmarking_cli::ExtrinsicBuilder for TransferKeepAliveBuilder {
 	}
 }
 
+/// Generates `KittiesModule::create` extrinsics for the benchmarks.
+///
+/// Note: Should only be used for benchmarking.
+pub struct CreateKittyBuilder {
+	client: Arc<FullClient>,
+}
+
+impl CreateKittyBuilder {
+	/// Creates a new [`Self`] from the given client.
+	pub fn new(client: Arc<FullClient>) -> Self {
+		Self { client }
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder for CreateKittyBuilder {
+	fn pallet(&self) -> &str {
+		"kitties"
+	}
+
+	fn extrinsic(&self) -> &str {
+		"create"
+	}
+
+	fn build(&self, nonce: u32) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		let acc = Sr25519Keyring::Bob.pair();
+		let extrinsic: OpaqueExtrinsic = create_benchmark_extrinsic(
+			self.client.as_ref(),
+			acc,
+			KittiesCall::create { name: *b"kitty\0\0\0" }.into(),
+			nonce,
+		)
+		.into();
+
+		Ok(extrinsic)
+	}
+}
+
+/// Generates `KittiesModule::breed` extrinsics for the benchmarks.
+///
+/// The parents are the kitties the `benchmark` chain starts with.
+///
+/// Note: Should only be used for benchmarking.
+pub struct BreedKittiesBuilder {
+	client: Arc<FullClient>,
+}
+
+impl BreedKittiesBuilder {
+	/// Creates a new [`Self`] from the given client.
+	pub fn new(client: Arc<FullClient>) -> Self {
+		Self { client }
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder for BreedKittiesBuilder {
+	fn pallet(&self) -> &str {
+		"kitties"
+	}
+
+	fn extrinsic(&self) -> &str {
+		"breed"
+	}
+
+	fn build(&self, nonce: u32) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		let acc = Sr25519Keyring::Bob.pair();
+		let (kitty_id_1, kitty_id_2) = BENCHMARK_PARENTS;
+		let extrinsic: OpaqueExtrinsic = create_benchmark_extrinsic(
+			self.client.as_ref(),
+			acc,
+			KittiesCall::breed { kitty_id_1, kitty_id_2, name: *b"kitten\0\0" }.into(),
+			nonce,
+		)
+		.into();
+
+		Ok(extrinsic)
+	}
+}
+
+/// Generates `KittiesModule::buy` extrinsics for the benchmarks.
+///
+/// Every extrinsic buys a different one of the kitties Alice has on sale in the `benchmark`
+/// chain.
+///
+/// Note: Should only be used for benchmarking.
+pub struct BuyKittyBuilder {
+	client: Arc<FullClient>,
+}
+
+impl BuyKittyBuilder {
+	/// Creates a new [`Self`] from the given client.
+	pub fn new(client: Arc<FullClient>) -> Self {
+		Self { client }
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder for BuyKittyBuilder {
+	fn pallet(&self) -> &str {
+		"kitties"
+	}
+
+	fn extrinsic(&self) -> &str {
+		"buy"
+	}
+
+	fn build(&self, nonce: u32) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		if nonce >= BENCHMARK_KITTIES_ON_SALE {
+			return Err("Not enough kitties on sale; lower `--max-ext-per-block`")
+		}
+
+		let acc = Sr25519Keyring::Bob.pair();
+		let extrinsic: OpaqueExtrinsic = create_benchmark_extrinsic(
+			self.client.as_ref(),
+			acc,
+			KittiesCall::buy { kitty_id: BENCHMARK_FIRST_KITTY_ON_SALE + nonce }.into(),
+			nonce,
+		)
+		.into();
+
+		Ok(extrinsic)
+	}
+}
+
 /// Create a transaction using the given `call`.
 ///
 /// Note: Should only be used for benchmarking.
@@ -1,6 +1,6 @@
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, Balance, BalancesConfig, GenesisConfig, GrandpaConfig,
+	KittiesModuleConfig, Signature, SudoConfig, SystemConfig, EXISTENTIAL_DEPOSIT, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -11,6 +11,13 @@ use sp_runtime::traits::{IdentifyAccount, Verify};
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
 
+/// The kitties the `benchmark` chain bred from, owned by Alice.
+pub const BENCHMARK_PARENTS: (u32, u32) = (0, 1);
+/// The first of the kitties Alice has on sale in the `benchmark` chain.
+pub const BENCHMARK_FIRST_KITTY_ON_SALE: u32 = 2;
+/// How many kitties Alice has on sale in the `benchmark` chain, enough to fill a block of `buy`s.
+pub const BENCHMARK_KITTIES_ON_SALE: u32 = 16_384;
+
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
 
@@ -59,6 +66,7 @@ pub fn development_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 				],
+				vec![],
 				true,
 			)
 		},
@@ -107,6 +115,7 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				vec![],
 				true,
 			)
 		},
@@ -124,12 +133,57 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 	))
 }
 
+/// The development chain, with the state the `benchmark extrinsic` builders rely on.
+pub fn benchmark_config() -> Result<ChainSpec, String> {
+	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+
+	Ok(ChainSpec::from_genesis(
+		// Name
+		"Benchmark",
+		// ID
+		"benchmark",
+		ChainType::Development,
+		move || {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let parents =
+				(0..BENCHMARK_FIRST_KITTY_ON_SALE).map(|_| (alice.clone(), *b"parent\0\0", None));
+			let on_sale = (0..BENCHMARK_KITTIES_ON_SALE)
+				.map(|_| (alice.clone(), *b"for sale", Some(EXISTENTIAL_DEPOSIT)));
+
+			testnet_genesis(
+				wasm_binary,
+				// Initial PoA authorities
+				vec![authority_keys_from_seed("Alice")],
+				// Sudo account
+				alice.clone(),
+				// Pre-funded accounts
+				vec![alice.clone(), get_account_id_from_seed::<sr25519::Public>("Bob")],
+				// Kitties
+				parents.chain(on_sale).collect(),
+				true,
+			)
+		},
+		// Bootnodes
+		vec![],
+		// Telemetry
+		None,
+		// Protocol ID
+		None,
+		None,
+		// Properties
+		None,
+		// Extensions
+		None,
+	))
+}
+
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	kitties: Vec<(AccountId, [u8; 8], Option<Balance>)>,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -152,5 +206,6 @@ fn testnet_genesis(
 			key: Some(root_key),
 		},
 		transaction_payment: Default::default(),
+		kitties_module: KittiesModuleConfig { kitties },
 	}
 }
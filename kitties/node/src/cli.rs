@@ -1,4 +1,5 @@
 use sc_cli::RunCmd;
+use std::str::FromStr;
 
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -7,6 +8,41 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// Seal blocks without Aura and GRANDPA, for development and integration tests.
+	///
+	/// One of `instant` (a block per transaction), `manual` (through the `engine_createBlock`
+	/// and `engine_finalizeBlock` RPCs) or `interval=<ms>`.
+	#[arg(long)]
+	pub sealing: Option<Sealing>,
+}
+
+/// How blocks are sealed when Aura and GRANDPA are replaced by manual seal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal a block as soon as a transaction enters the pool, and finalize it.
+	Instant,
+	/// Seal and finalize blocks when requested through RPC.
+	Manual,
+	/// Seal and finalize a block every given number of milliseconds.
+	Interval(u64),
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"instant" => Ok(Sealing::Instant),
+			"manual" => Ok(Sealing::Manual),
+			_ => match s.strip_prefix("interval=").map(str::parse) {
+				Some(Ok(millis)) if millis > 0 => Ok(Sealing::Interval(millis)),
+				_ => Err(format!(
+					"invalid sealing `{s}`, expected `instant`, `manual` or `interval=<ms>`"
+				)),
+			},
+		}
+	}
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -50,4 +86,7 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Inspect the kitties in the local database.
+	Kitties(crate::inspect::KittiesCmd),
 }
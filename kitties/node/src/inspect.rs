@@ -0,0 +1,216 @@
+//! The `kitties` subcommand, which reads kitties straight from the local database.
+
+use crate::service::FullClient;
+
+use node_template_runtime::{AccountId, Balance, Hash, Runtime};
+use pallet_kitties::{
+	Kitties, Kitty, KittyId, KittyOwner, KittyParents, KittyPrices, NextKittyId, Rarity,
+};
+use sc_cli::{
+	BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, Result, SharedParams,
+};
+use sc_client_api::StorageProvider;
+use serde::Serialize;
+use sp_blockchain::HeaderBackend;
+use sp_core::{hexdisplay::HexDisplay, storage::StorageKey, Decode};
+
+/// How the `kitties` subcommand prints what it finds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+	/// One line per kitty.
+	Table,
+	/// A JSON document.
+	Json,
+}
+
+/// The `kitties` command used to inspect kitties without a running node.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct KittiesCmd {
+	/// Block hash or number to inspect, the best block by default.
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// How to print the result.
+	#[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+	pub output: OutputFormat,
+
+	/// Export the lineage tree of this kitty instead of listing all kitties.
+	#[arg(long, value_name = "KITTY_ID")]
+	pub lineage: Option<KittyId>,
+
+	/// How many generations of ancestors the lineage tree goes back.
+	#[arg(long, default_value_t = 8)]
+	pub depth: u32,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+/// A kitty as listed by the `kitties` command.
+#[derive(Serialize)]
+struct KittyInfo {
+	id: KittyId,
+	name: String,
+	dna: String,
+	generation: u32,
+	rarity: Rarity,
+	owner: Option<AccountId>,
+	parents: Option<(KittyId, KittyId)>,
+	price: Option<Balance>,
+}
+
+/// A kitty and its ancestors. A burned ancestor has no name.
+#[derive(Serialize)]
+struct Lineage {
+	id: KittyId,
+	name: Option<String>,
+	parents: Vec<Lineage>,
+}
+
+impl KittiesCmd {
+	/// Run the command against the state of the requested block.
+	pub fn run(&self, client: &FullClient) -> Result<()> {
+		let at = match &self.at {
+			Some(at) => client.expect_block_hash_from_id(&at.parse()?)?,
+			None => client.info().best_hash,
+		};
+		let state = State { client, at };
+
+		match self.lineage {
+			Some(kitty_id) => {
+				if state.get::<Kitty>(Kitties::<Runtime>::hashed_key_for(kitty_id))?.is_none() {
+					return Err(format!("kitty {} does not exist", kitty_id).into())
+				}
+				let lineage = state.lineage(kitty_id, self.depth)?;
+				match self.output {
+					OutputFormat::Json => print_json(&lineage)?,
+					OutputFormat::Table => print_lineage(&lineage, 0),
+				}
+			},
+			None => {
+				let kitties = state.kitties()?;
+				match self.output {
+					OutputFormat::Json => print_json(&kitties)?,
+					OutputFormat::Table => print_kitties(&kitties),
+				}
+			},
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for KittiesCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// The `pallet_kitties` storage at a given block.
+struct State<'a> {
+	client: &'a FullClient,
+	at: Hash,
+}
+
+impl State<'_> {
+	fn get<T: Decode>(&self, key: impl Into<Vec<u8>>) -> Result<Option<T>> {
+		self.client
+			.storage(self.at, &StorageKey(key.into()))?
+			.map(|data| T::decode(&mut &data.0[..]))
+			.transpose()
+			.map_err(|e| format!("failed to decode kitties storage: {}", e).into())
+	}
+
+	fn parents(&self, kitty_id: KittyId) -> Result<Option<(KittyId, KittyId)>> {
+		self.get(KittyParents::<Runtime>::hashed_key_for(kitty_id))
+	}
+
+	/// Every kitty that has not been burned, by id.
+	fn kitties(&self) -> Result<Vec<KittyInfo>> {
+		let next_id: KittyId = self.get(NextKittyId::<Runtime>::hashed_key())?.unwrap_or_default();
+
+		let mut kitties = Vec::new();
+		for id in 0..next_id {
+			let kitty = match self.get::<Kitty>(Kitties::<Runtime>::hashed_key_for(id))? {
+				Some(kitty) => kitty,
+				// Burned.
+				None => continue,
+			};
+			kitties.push(KittyInfo {
+				id,
+				name: kitty_name(&kitty),
+				dna: format!("0x{}", HexDisplay::from(&kitty.dna)),
+				generation: kitty.generation,
+				rarity: kitty.rarity,
+				owner: self.get(KittyOwner::<Runtime>::hashed_key_for(id))?,
+				parents: self.parents(id)?,
+				price: self.get(KittyPrices::<Runtime>::hashed_key_for(id))?,
+			});
+		}
+
+		Ok(kitties)
+	}
+
+	fn lineage(&self, kitty_id: KittyId, depth: u32) -> Result<Lineage> {
+		let name = self.get::<Kitty>(Kitties::<Runtime>::hashed_key_for(kitty_id))?;
+		let parents = match self.parents(kitty_id)? {
+			Some((parent_1, parent_2)) if depth > 0 =>
+				vec![self.lineage(parent_1, depth - 1)?, self.lineage(parent_2, depth - 1)?],
+			_ => Vec::new(),
+		};
+
+		Ok(Lineage { id: kitty_id, name: name.as_ref().map(kitty_name), parents })
+	}
+}
+
+fn kitty_name(kitty: &Kitty) -> String {
+	String::from_utf8_lossy(&kitty.name).trim_end_matches('\0').to_string()
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+	let json = serde_json::to_string_pretty(value)
+		.map_err(|e| format!("failed to serialize kitties: {}", e))?;
+	println!("{}", json);
+	Ok(())
+}
+
+fn print_kitties(kitties: &[KittyInfo]) {
+	println!(
+		"{:>6}  {:<8}  {:<34}  {:>3}  {:>6}  {:<48}  {:<13}  {}",
+		"ID", "NAME", "DNA", "GEN", "RARITY", "OWNER", "PARENTS", "PRICE"
+	);
+	for kitty in kitties {
+		let owner = kitty.owner.as_ref().map_or_else(|| "-".to_string(), ToString::to_string);
+		let parents = kitty.parents.map_or_else(|| "-".to_string(), |(a, b)| format!("{a},{b}"));
+		let price = kitty.price.map_or_else(|| "-".to_string(), |price| price.to_string());
+		println!(
+			"{:>6}  {:<8}  {:<34}  {:>3}  {:>6}  {:<48}  {:<13}  {}",
+			kitty.id, kitty.name, kitty.dna, kitty.generation, kitty.rarity, owner, parents, price
+		);
+	}
+}
+
+fn print_lineage(lineage: &Lineage, indent: usize) {
+	let name = lineage.name.as_deref().unwrap_or("(burned)");
+	println!("{:indent$}{} {}", "", lineage.id, name, indent = indent * 2);
+	for parent in &lineage.parents {
+		print_lineage(parent, indent + 1);
+	}
+}
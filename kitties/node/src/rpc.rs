@@ -7,46 +7,500 @@
 
 use std::sync::Arc;
 
-use jsonrpsee::RpcModule;
-use node_template_runtime::{opaque::Block, AccountId, Balance, Index};
+use futures::channel::mpsc;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::{
+		error::{CallError, ErrorObject},
+		SubscriptionResult,
+	},
+	RpcModule, SubscriptionSink,
+};
+use node_rpc_events::spawn_event_subscription;
+use node_template_runtime::{
+	opaque::Block, AccountId, Balance, BlockNumber, Hash, Index, Runtime, RuntimeEvent, DAYS,
+};
+use pallet_kitties::{
+	offchain::{owned_kitties_key, sale_key, SaleRecord},
+	Kitty, KittyId, LastAccountSale, LastKittySale, LastSale,
+};
+use sc_client_api::{Backend, BlockchainEvents, StateBackend};
+use sc_consensus_manual_seal::EngineCommand;
+use sc_rpc::SubscriptionTaskExecutor;
 use sc_transaction_pool_api::TransactionPool;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_core::{
+	hexdisplay::HexDisplay,
+	offchain::{OffchainStorage, STORAGE_PREFIX},
+	Decode,
+};
 
 pub use sc_rpc_api::DenyUnsafe;
 
 /// Full client dependencies.
-pub struct FullDeps<C, P> {
+pub struct FullDeps<C, P, B, S> {
 	/// The client instance to use.
 	pub client: Arc<C>,
+	/// The backend the client reads state from.
+	pub backend: Arc<B>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Manual seal command sink, set when blocks are sealed on request.
+	pub command_sink: Option<mpsc::Sender<EngineCommand<Hash>>>,
+	/// Offchain storage holding the kitties index, if the backend has one.
+	pub offchain_storage: Option<S>,
+	/// Executor for subscription tasks.
+	pub subscription_executor: SubscriptionTaskExecutor,
+}
+
+/// The most sales returned in one page.
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// The most blocks [`KittiesApiServer::price_stats`] looks back.
+const MAX_STATS_WINDOW: BlockNumber = 7 * DAYS;
+
+/// The most sales one request reads from the offchain database.
+const MAX_SALES_SCANNED: usize = 1_000;
+
+type SaleRecordOf = SaleRecord<AccountId, Balance, BlockNumber, Hash>;
+
+/// A kitty sale, as returned by the [`KittiesApiServer`] sale queries.
+#[derive(Serialize)]
+pub struct Sale {
+	/// The kitty sold.
+	pub kitty_id: KittyId,
+	/// The previous owner.
+	pub seller: AccountId,
+	/// The new owner.
+	pub buyer: AccountId,
+	/// The price paid.
+	pub price: Balance,
+	/// The block the kitty was sold in.
+	pub block_number: BlockNumber,
+}
+
+impl From<SaleRecordOf> for Sale {
+	fn from(sale: SaleRecordOf) -> Self {
+		let SaleRecord { kitty_id, seller, buyer, price, block_number, .. } = sale;
+		Self { kitty_id, seller, buyer, price, block_number }
+	}
+}
+
+/// Sale prices over a window of blocks, as returned by [`KittiesApiServer::price_stats`].
+#[derive(Serialize)]
+pub struct PriceStats {
+	/// The number of sales in the window.
+	pub sales: u32,
+	/// The lowest price paid, if there were any sales.
+	pub floor: Option<Balance>,
+	/// The mean price paid, if there were any sales.
+	pub average: Option<Balance>,
+	/// Whether the window may hold more sales than are read, in which case the stats only
+	/// cover the newest ones.
+	pub truncated: bool,
+}
+
+/// Kitties RPC methods, served from the offchain database.
+///
+/// The owner index only covers the blocks the offchain worker ran on, see `--offchain-worker`,
+/// and sales are only recorded with `--enable-offchain-indexing`. Sales are followed back from
+/// the latest ones in the state of the best block, so sales of retracted blocks are left out.
+/// No request reads more than the newest thousand sales it follows.
+#[rpc(server)]
+pub trait KittiesApi {
+	/// The kitties owned by `account`, as far as the offchain worker indexed them. Changes of
+	/// owner in blocks the worker skipped, e.g. during a major sync, are missing.
+	#[method(name = "kitties_ownedBy")]
+	fn owned_by(&self, account: AccountId) -> RpcResult<Vec<KittyId>>;
+
+	/// A page of the sales of `kitty_id`, newest first. Fails for pages past the thousandth
+	/// sale.
+	#[method(name = "kitties_kittySales")]
+	fn kitty_sales(&self, kitty_id: KittyId, page: u32, page_size: u32) -> RpcResult<Vec<Sale>>;
+
+	/// A page of the sales `account` bought or sold in, newest first. Fails for pages past the
+	/// thousandth sale.
+	#[method(name = "kitties_accountSales")]
+	fn account_sales(&self, account: AccountId, page: u32, page_size: u32) -> RpcResult<Vec<Sale>>;
+
+	/// Sale prices over the last `window` blocks, of `kitty_id` or of all kitties. The window
+	/// is capped at a week, and at most the newest thousand sales in it are counted.
+	#[method(name = "kitties_priceStats")]
+	fn price_stats(&self, window: BlockNumber, kitty_id: Option<KittyId>) -> RpcResult<PriceStats>;
+}
+
+/// Implements [`KittiesApiServer`] by reading the offchain database.
+pub struct KittiesIndex<C, B, S> {
+	client: Arc<C>,
+	backend: Arc<B>,
+	storage: S,
+}
+
+impl<C, B, S> KittiesIndex<C, B, S>
+where
+	C: HeaderBackend<Block>,
+	B: Backend<Block>,
+	S: OffchainStorage,
+{
+	/// Create a new [`KittiesIndex`] RPC handler.
+	pub fn new(client: Arc<C>, backend: Arc<B>, storage: S) -> Self {
+		Self { client, backend, storage }
+	}
+
+	fn get<T: Decode>(&self, key: &[u8]) -> RpcResult<Option<T>> {
+		self.storage
+			.get(STORAGE_PREFIX, key)
+			.map(|value| T::decode(&mut &value[..]))
+			.transpose()
+			.map_err(|e| index_error("Unable to decode the kitties index.", e))
+	}
+
+	/// The digest stored under `key` in the state of the best block.
+	fn latest_sale(&self, key: &[u8]) -> RpcResult<Option<Hash>> {
+		let best_hash = self.client.info().best_hash;
+		self.backend
+			.state_at(best_hash)
+			.map_err(|e| index_error("Unable to read the best block state.", e))?
+			.storage(key)
+			.map_err(|e| index_error("Unable to read the best block state.", e))?
+			.map(|value| Hash::decode(&mut &value[..]))
+			.transpose()
+			.map_err(|e| index_error("Unable to decode the latest sale.", e))
+	}
+
+	/// The sales from `latest` back, newest first, with `previous` giving the link to follow.
+	///
+	/// Stops at the first sale missing from the database, e.g. one made while the node ran
+	/// without offchain indexing, or after [`MAX_SALES_SCANNED`] sales.
+	fn sales<'a>(
+		&'a self,
+		latest: Option<Hash>,
+		previous: impl Fn(&SaleRecordOf) -> Option<Hash> + 'a,
+	) -> impl Iterator<Item = RpcResult<SaleRecordOf>> + 'a {
+		let mut next = latest;
+		std::iter::from_fn(move || {
+			let sale = self.get::<SaleRecordOf>(&sale_key(&next.take()?)).transpose()?;
+			next = sale.as_ref().ok().and_then(&previous);
+			Some(sale)
+		})
+		.take(MAX_SALES_SCANNED)
+	}
+
+	fn page(
+		&self,
+		sales: impl Iterator<Item = RpcResult<SaleRecordOf>>,
+		page: u32,
+		page_size: u32,
+	) -> RpcResult<Vec<Sale>> {
+		let page_size = page_size.min(MAX_PAGE_SIZE) as usize;
+		let offset = (page as usize).saturating_mul(page_size);
+		if offset.saturating_add(page_size) > MAX_SALES_SCANNED {
+			return Err(index_error(
+				"Page out of range.",
+				format!("only the newest {} sales can be paged through", MAX_SALES_SCANNED),
+			))
+		}
+		sales.skip(offset).take(page_size).map(|sale| sale.map(Into::into)).collect()
+	}
+}
+
+fn index_error(message: &'static str, e: impl ToString) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(1, message, Some(e.to_string()))).into()
+}
+
+impl<C, B, S> KittiesApiServer for KittiesIndex<C, B, S>
+where
+	C: HeaderBackend<Block> + Send + Sync + 'static,
+	B: Backend<Block> + Send + Sync + 'static,
+	S: OffchainStorage + 'static,
+{
+	fn owned_by(&self, account: AccountId) -> RpcResult<Vec<KittyId>> {
+		Ok(self.get(&owned_kitties_key(&account))?.unwrap_or_default())
+	}
+
+	fn kitty_sales(&self, kitty_id: KittyId, page: u32, page_size: u32) -> RpcResult<Vec<Sale>> {
+		let latest = self.latest_sale(&LastKittySale::<Runtime>::hashed_key_for(kitty_id))?;
+		self.page(self.sales(latest, |sale| sale.previous_of_kitty), page, page_size)
+	}
+
+	fn account_sales(&self, account: AccountId, page: u32, page_size: u32) -> RpcResult<Vec<Sale>> {
+		let latest = self.latest_sale(&LastAccountSale::<Runtime>::hashed_key_for(&account))?;
+		let previous = move |sale: &SaleRecordOf| {
+			if sale.seller == account {
+				sale.previous_of_seller
+			} else {
+				sale.previous_of_buyer
+			}
+		};
+		self.page(self.sales(latest, previous), page, page_size)
+	}
+
+	fn price_stats(&self, window: BlockNumber, kitty_id: Option<KittyId>) -> RpcResult<PriceStats> {
+		let since = self.client.info().best_number.saturating_sub(window.min(MAX_STATS_WINDOW));
+		let sales: Box<dyn Iterator<Item = _>> = match kitty_id {
+			Some(kitty_id) => {
+				let key = LastKittySale::<Runtime>::hashed_key_for(kitty_id);
+				Box::new(self.sales(self.latest_sale(&key)?, |sale| sale.previous_of_kitty))
+			},
+			None => {
+				let latest = self.latest_sale(&LastSale::<Runtime>::hashed_key())?;
+				Box::new(self.sales(latest, |sale| sale.previous))
+			},
+		};
+
+		let mut stats = PriceStats { sales: 0, floor: None, average: None, truncated: false };
+		let mut total: Balance = 0;
+		for sale in sales {
+			let sale = sale?;
+			if sale.block_number <= since {
+				break
+			}
+			stats.sales += 1;
+			stats.floor = Some(stats.floor.map_or(sale.price, |floor| floor.min(sale.price)));
+			total = total.saturating_add(sale.price);
+		}
+		// Every sale read was in the window, so older ones may be too.
+		stats.truncated = stats.sales as usize == MAX_SALES_SCANNED;
+		if stats.sales > 0 {
+			stats.average = Some(total / Balance::from(stats.sales));
+		}
+
+		Ok(stats)
+	}
+}
+
+/// Which events [`KittiesEventsApiServer::subscribe_events`] streams. Unset filters match any
+/// event.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventFilter {
+	/// Only events involving this account.
+	pub account: Option<AccountId>,
+	/// Only events about this kitty.
+	pub kitty_id: Option<KittyId>,
+}
+
+impl EventFilter {
+	fn matches(&self, event: &EventNotification) -> bool {
+		self.account.as_ref().map_or(true, |account| event.accounts.contains(account)) &&
+			self.kitty_id.map_or(true, |kitty_id| event.kitty_id == Some(kitty_id))
+	}
+}
+
+/// A `pallet_kitties` event, as streamed by [`KittiesEventsApiServer::subscribe_events`].
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventNotification {
+	/// The block the event was deposited in.
+	pub block_hash: Hash,
+	/// The number of that block.
+	pub block_number: BlockNumber,
+	/// The event variant, e.g. `KittySold`.
+	pub name: &'static str,
+	/// The event fields.
+	pub data: Value,
+	#[serde(skip)]
+	accounts: Vec<AccountId>,
+	#[serde(skip)]
+	kitty_id: Option<KittyId>,
+}
+
+impl EventNotification {
+	fn new(
+		block_hash: Hash,
+		block_number: BlockNumber,
+		event: pallet_kitties::Event<Runtime>,
+	) -> Option<Self> {
+		use pallet_kitties::Event::*;
+
+		let (name, data, accounts, kitty_id) = match event {
+			KittyCreated { who, kitty_id, kitty } => (
+				"KittyCreated",
+				json!({ "who": who, "kittyId": kitty_id, "kitty": kitty_json(&kitty) }),
+				vec![who],
+				Some(kitty_id),
+			),
+			KittyBred { who, kitty_id, kitty } => (
+				"KittyBred",
+				json!({ "who": who, "kittyId": kitty_id, "kitty": kitty_json(&kitty) }),
+				vec![who],
+				Some(kitty_id),
+			),
+			KittyTransferred { from, to, kitty_id } => (
+				"KittyTransferred",
+				json!({ "from": from, "to": to, "kittyId": kitty_id }),
+				vec![from, to],
+				Some(kitty_id),
+			),
+			KittyForSale { who, kitty_id, price } => (
+				"KittyForSale",
+				json!({ "who": who, "kittyId": kitty_id, "price": price }),
+				vec![who],
+				Some(kitty_id),
+			),
+			KittySold { from, to, kitty_id, price } => (
+				"KittySold",
+				json!({ "from": from, "to": to, "kittyId": kitty_id, "price": price }),
+				vec![from, to],
+				Some(kitty_id),
+			),
+			KittyBurned { owner, kitty_id } => (
+				"KittyBurned",
+				json!({ "owner": owner, "kittyId": kitty_id }),
+				vec![owner],
+				Some(kitty_id),
+			),
+			MintPriceSet { price } => ("MintPriceSet", json!({ "price": price }), vec![], None),
+			CallGroupPauseSet { group, paused } => (
+				"CallGroupPauseSet",
+				json!({ "group": format!("{:?}", group), "paused": paused }),
+				vec![],
+				None,
+			),
+			KittyStaked { who, kitty_id } => (
+				"KittyStaked",
+				json!({ "who": who, "kittyId": kitty_id }),
+				vec![who],
+				Some(kitty_id),
+			),
+			KittyUnstaked { who, kitty_id, unbonding_until } => (
+				"KittyUnstaked",
+				json!({ "who": who, "kittyId": kitty_id, "unbondingUntil": unbonding_until }),
+				vec![who],
+				Some(kitty_id),
+			),
+			RewardsClaimed { who, kitty_id, amount } => (
+				"RewardsClaimed",
+				json!({ "who": who, "kittyId": kitty_id, "amount": amount }),
+				vec![who],
+				Some(kitty_id),
+			),
+			KittyReleased { who, kitty_id } => (
+				"KittyReleased",
+				json!({ "who": who, "kittyId": kitty_id }),
+				vec![who],
+				Some(kitty_id),
+			),
+			_ => return None,
+		};
+
+		Some(Self { block_hash, block_number, name, data, accounts, kitty_id })
+	}
+}
+
+fn kitty_json(kitty: &Kitty) -> Value {
+	json!({
+		"dna": format!("0x{}", HexDisplay::from(&kitty.dna)),
+		"name": String::from_utf8_lossy(&kitty.name).trim_end_matches('\0'),
+		"generation": kitty.generation,
+		"rarity": kitty.rarity,
+	})
+}
+
+/// Kitties event subscriptions.
+#[rpc(server)]
+pub trait KittiesEventsApi {
+	/// Stream the `pallet_kitties` events of every new best block that pass `filter`.
+	#[subscription(
+		name = "kitties_subscribeEvents" => "kitties_event",
+		unsubscribe = "kitties_unsubscribeEvents",
+		item = EventNotification,
+	)]
+	fn subscribe_events(&self, filter: Option<EventFilter>);
+}
+
+/// Implements [`KittiesEventsApiServer`] from block import notifications.
+pub struct KittiesEvents<C, B> {
+	client: Arc<C>,
+	backend: Arc<B>,
+	executor: SubscriptionTaskExecutor,
+}
+
+impl<C, B> KittiesEvents<C, B> {
+	/// Create a new [`KittiesEvents`] RPC handler.
+	pub fn new(client: Arc<C>, backend: Arc<B>, executor: SubscriptionTaskExecutor) -> Self {
+		Self { client, backend, executor }
+	}
+}
+
+impl<C, B> KittiesEventsApiServer for KittiesEvents<C, B>
+where
+	C: BlockchainEvents<Block> + Send + Sync + 'static,
+	B: Backend<Block> + Send + Sync + 'static,
+{
+	fn subscribe_events(
+		&self,
+		sink: SubscriptionSink,
+		filter: Option<EventFilter>,
+	) -> SubscriptionResult {
+		let filter = filter.unwrap_or_default();
+		spawn_event_subscription::<Block, RuntimeEvent, _, _, _, _>(
+			"kitties-rpc-subscription",
+			&*self.client,
+			self.backend.clone(),
+			&self.executor,
+			sink,
+			move |hash, number, event| match event {
+				RuntimeEvent::KittiesModule(event) => EventNotification::new(hash, number, event)
+					.filter(|event| filter.matches(event)),
+				_ => None,
+			},
+		);
+		Ok(())
+	}
 }
 
 /// Instantiate all full RPC extensions.
-pub fn create_full<C, P>(
-	deps: FullDeps<C, P>,
+pub fn create_full<C, P, B, S>(
+	deps: FullDeps<C, P, B, S>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
 	C: ProvideRuntimeApi<Block>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + 'static,
-	C: Send + Sync + 'static,
+	C: BlockchainEvents<Block> + Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
+	B: Backend<Block> + Send + Sync + 'static,
+	S: OffchainStorage + 'static,
 {
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
+	use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps {
+		client,
+		backend,
+		pool,
+		deny_unsafe,
+		command_sink,
+		offchain_storage,
+		subscription_executor,
+	} = deps;
 
 	module.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
-	module.merge(TransactionPayment::new(client).into_rpc())?;
+	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+
+	if let Some(command_sink) = command_sink {
+		// `engine_createBlock` and `engine_finalizeBlock`.
+		module.merge(ManualSeal::new(command_sink).into_rpc())?;
+	}
+
+	if let Some(offchain_storage) = offchain_storage {
+		module.merge(
+			KittiesIndex::new(client.clone(), backend.clone(), offchain_storage).into_rpc(),
+		)?;
+	}
+	module.merge(KittiesEvents::new(client, backend, subscription_executor).into_rpc())?;
 
 	// Extend this RPC with a custom API by using the following syntax.
 	// `YourRpcStruct` should have a reference to a client, which is needed
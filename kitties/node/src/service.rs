@@ -1,14 +1,27 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
+use crate::cli::Sealing;
+use futures::{channel::mpsc, prelude::*};
 use node_template_runtime::{self, opaque::Block, RuntimeApi};
-use sc_client_api::BlockBackend;
+use sc_client_api::{Backend, BlockBackend, StorageProvider};
 use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
 use sc_consensus_grandpa::SharedVoterState;
+use sc_consensus_manual_seal::{
+	consensus::aura::AuraConsensusDataProvider, EngineCommand, ManualSealParams,
+};
 pub use sc_executor::NativeElseWasmExecutor;
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager, WarpSyncParams};
 use sc_telemetry::{Telemetry, TelemetryWorker};
+use sp_blockchain::HeaderBackend;
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
-use std::{sync::Arc, time::Duration};
+use sp_core::{storage::StorageKey, twox_128};
+use std::{
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
 
 // Our native executor instance.
 pub struct ExecutorDispatch;
@@ -37,6 +50,7 @@ type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 
 pub fn new_partial(
 	config: &Configuration,
+	sealing: Option<Sealing>,
 ) -> Result<
 	sc_service::PartialComponents<
 		FullClient,
@@ -102,7 +116,14 @@ pub fn new_partial(
 
 	let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
 
-	let import_queue =
+	// Manually sealed blocks carry no Aura seal, so they are imported as they are.
+	let import_queue = if sealing.is_some() {
+		sc_consensus_manual_seal::import_queue(
+			Box::new(client.clone()),
+			&task_manager.spawn_essential_handle(),
+			config.prometheus_registry(),
+		)
+	} else {
 		sc_consensus_aura::import_queue::<AuraPair, _, _, _, _, _>(ImportQueueParams {
 			block_import: grandpa_block_import.clone(),
 			justification_import: Some(Box::new(grandpa_block_import.clone())),
@@ -123,7 +144,8 @@ pub fn new_partial(
 			check_for_equivocation: Default::default(),
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 			compatibility_mode: Default::default(),
-		})?;
+		})?
+	};
 
 	Ok(sc_service::PartialComponents {
 		client,
@@ -138,7 +160,13 @@ pub fn new_partial(
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+///
+/// With `sealing` set, blocks are authored and finalized by manual seal instead of Aura and
+/// GRANDPA.
+pub fn new_full(
+	mut config: Configuration,
+	sealing: Option<Sealing>,
+) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -148,7 +176,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		select_chain,
 		transaction_pool,
 		other: (block_import, grandpa_link, mut telemetry),
-	} = new_partial(&config)?;
+	} = new_partial(&config, sealing)?;
 
 	let grandpa_protocol_name = sc_consensus_grandpa::protocol_standard_name(
 		&client.block_hash(0).ok().flatten().expect("Genesis block exists; qed"),
@@ -189,16 +217,34 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let force_authoring = config.force_authoring;
 	let backoff_authoring_blocks: Option<()> = None;
 	let name = config.network.node_name.clone();
-	let enable_grandpa = !config.disable_grandpa;
+	let enable_grandpa = !config.disable_grandpa && sealing.is_none();
 	let prometheus_registry = config.prometheus_registry().cloned();
 
+	// Blocks are sealed on request through the `engine_*` RPCs in manual mode.
+	let (command_sink, commands_stream) = match sealing {
+		Some(Sealing::Manual) => {
+			let (sink, stream) = mpsc::channel(1024);
+			(Some(sink), Some(stream))
+		},
+		_ => (None, None),
+	};
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
-
-		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+		let backend = backend.clone();
+		let offchain_storage = backend.offchain_storage();
+
+		Box::new(move |deny_unsafe, subscription_executor| {
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				backend: backend.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				command_sink: command_sink.clone(),
+				offchain_storage: offchain_storage.clone(),
+				subscription_executor,
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
@@ -218,7 +264,69 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		telemetry: telemetry.as_mut(),
 	})?;
 
-	if role.is_authority() {
+	if let Some(sealing) = sealing {
+		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+			task_manager.spawn_handle(),
+			client.clone(),
+			transaction_pool.clone(),
+			prometheus_registry.as_ref(),
+			telemetry.as_ref().map(|x| x.handle()),
+		);
+
+		let slot_duration = sc_consensus_aura::slot_duration(&*client)?.as_millis();
+		let last_timestamp = Arc::new(AtomicU64::new(best_block_timestamp(&client)));
+		let create_inherent_data_providers = move |_, ()| {
+			let timestamp = next_timestamp(&last_timestamp, slot_duration);
+			async move { Ok(sp_timestamp::InherentDataProvider::new(timestamp.into())) }
+		};
+
+		let commands_stream: Box<dyn Stream<Item = EngineCommand<_>> + Send + Sync + Unpin> =
+			match sealing {
+				Sealing::Manual => {
+					Box::new(commands_stream.expect("created for manual sealing; qed"))
+				},
+				Sealing::Instant => Box::new(
+					// Seal a block for every transaction imported into the pool.
+					transaction_pool.pool().validated_pool().import_notification_stream().map(
+						|_| EngineCommand::SealNewBlock {
+							create_empty: false,
+							finalize: true,
+							parent_hash: None,
+							sender: None,
+						},
+					),
+				),
+				Sealing::Interval(millis) => {
+					Box::new(Box::pin(futures::stream::unfold((), move |()| async move {
+						futures_timer::Delay::new(Duration::from_millis(millis)).await;
+						let command = EngineCommand::SealNewBlock {
+							create_empty: true,
+							finalize: true,
+							parent_hash: None,
+							sender: None,
+						};
+						Some((command, ()))
+					})))
+				},
+			};
+
+		let manual_seal = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env: proposer_factory,
+			client: client.clone(),
+			pool: transaction_pool,
+			commands_stream,
+			select_chain,
+			consensus_data_provider: Some(Box::new(AuraConsensusDataProvider::new(client))),
+			create_inherent_data_providers,
+		});
+
+		task_manager.spawn_essential_handle().spawn_blocking(
+			"manual-seal",
+			Some("block-authoring"),
+			manual_seal,
+		);
+	} else if role.is_authority() {
 		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
 			client.clone(),
@@ -312,3 +420,26 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	network_starter.start_network();
 	Ok(task_manager)
 }
+
+/// The timestamp of the best block, read from `pallet_timestamp` storage.
+fn best_block_timestamp(client: &FullClient) -> u64 {
+	let key = [twox_128(b"Timestamp"), twox_128(b"Now")].concat();
+	client
+		.storage(client.info().best_hash, &StorageKey(key))
+		.ok()
+		.flatten()
+		.and_then(|data| data.0.try_into().ok())
+		.map_or(0, u64::from_le_bytes)
+}
+
+/// The timestamp of the next manually sealed block.
+///
+/// This is the current time, but at least one slot after the previous block, since
+/// `pallet_aura` requires every block to be in a later slot than its parent.
+fn next_timestamp(last_timestamp: &AtomicU64, slot_duration: u64) -> u64 {
+	let now = sp_timestamp::Timestamp::current().as_millis();
+	let previous = last_timestamp.load(Ordering::SeqCst);
+	let next = now.max(previous.saturating_add(slot_duration));
+	last_timestamp.store(next, Ordering::SeqCst);
+	next
+}
@@ -0,0 +1,30 @@
+//! Runtime API definition for the kitties pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_runtime::Perbill;
+use sp_std::vec::Vec;
+
+pub use pallet_kitties::{FamilyMember, KittyId, Rarity};
+
+sp_api::decl_runtime_apis! {
+	/// Queries over the family trees and rarity of `pallet_kitties` kitties.
+	pub trait KittiesApi {
+		/// The kitty and its ancestors up to `depth` generations back, closest first.
+		fn ancestry(kitty_id: KittyId, depth: u32) -> Vec<FamilyMember>;
+
+		/// The kitty and its descendants up to `depth` generations down, closest first.
+		fn descendants(kitty_id: KittyId, depth: u32) -> Vec<FamilyMember>;
+
+		/// The inbreeding coefficient of the kitty.
+		fn inbreeding_coefficient(kitty_id: KittyId) -> Perbill;
+
+		/// The inbreeding coefficient a kitty bred from the two parents would have.
+		fn offspring_inbreeding_coefficient(parent_1: KittyId, parent_2: KittyId) -> Perbill;
+
+		/// The rarest kitties as `(kitty_id, rarity)`, rarest first, of those minted or bred
+		/// since the board was filled. Burned kitties leave gaps that only new kitties fill, so
+		/// rarer kitties pushed off the board before are not listed.
+		fn leaderboard() -> Vec<(KittyId, Rarity)>;
+	}
+}
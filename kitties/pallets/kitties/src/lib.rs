@@ -2,7 +2,15 @@
 
 pub use pallet::*;
 
+pub mod lineage;
 mod migrations;
+pub mod offchain;
+pub mod rarity;
+pub mod staking;
+
+pub use lineage::{FamilyMember, MAX_LINEAGE_DEPTH};
+pub use rarity::{rarity_score, Rarity};
+pub use staking::StakeInfo;
 
 #[cfg(test)]
 mod mock;
@@ -19,7 +27,7 @@ pub mod pallet {
 	use sp_io::hashing::blake2_128;
 	use frame_support::traits::{Randomness, Currency, ExistenceRequirement, ReservableCurrency};
 	use frame_support::PalletId;
-	use sp_runtime::traits::AccountIdConversion;
+	use sp_runtime::traits::{AccountIdConversion, Saturating, Zero};
 	use crate::migrations;
 
 	pub type KittyId = u32;
@@ -30,9 +38,26 @@ pub mod pallet {
 	pub struct Kitty {
 		pub dna: [u8; 16],
 		pub name: [u8; 8],
+		/// 0 for a created kitty, one more than its older parent's for a bred one.
+		pub generation: u32,
+		/// The rarity score of the kitty, fixed when it is minted.
+		pub rarity: Rarity,
+	}
+
+	/// A group of calls that can be paused together.
+	#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+	pub enum CallGroup {
+		/// `create` and `breed`.
+		Minting,
+		/// `transfer`.
+		Transfers,
+		/// `set_price` and `buy`.
+		Trading,
+		/// `stake`, `unstake` and `claim_rewards`.
+		Staking,
 	}
 
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
@@ -42,13 +67,24 @@ pub mod pallet {
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
-		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent> + TryInto<Event<Self>>;
 		type KittyRandomness: Randomness<Self::Hash, Self::BlockNumber>;
 		// type Currency: ReservableCurrency<Self::AccountId>;
 		type Currency: Currency<Self::AccountId>;
         #[pallet::constant]
         type KittyPrice: Get<BalanceOf<Self>>;
 		type PalletId: Get<PalletId>;
+		/// The origin allowed to move or burn any kitty and to control minting.
+		type ForceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// How many kitties the leaderboard holds.
+		#[pallet::constant]
+		type LeaderboardSize: Get<u32>;
+		/// The reward a staked kitty earns per block for each point of its rarity.
+		#[pallet::constant]
+		type StakingReward: Get<BalanceOf<Self>>;
+		/// How many blocks an unstaked kitty stays locked before it can be released.
+		#[pallet::constant]
+		type UnbondingPeriod: Get<Self::BlockNumber>;
 	}
 
 	// The pallet's runtime storage items.
@@ -68,10 +104,89 @@ pub mod pallet {
 	#[pallet::getter(fn kitty_parents)]
 	pub type KittyParents<T: Config> = StorageMap<_, Blake2_128Concat, KittyId, (KittyId, KittyId), OptionQuery>;
 
+	/// The kitties bred from each kitty, as `(parent, child) => ()`.
+	#[pallet::storage]
+	pub type KittyChildren<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, KittyId, Blake2_128Concat, KittyId, (), OptionQuery>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn kitty_prices)]
 	pub type KittyPrices<T: Config> = StorageMap<_, Blake2_128Concat, KittyId, BalanceOf<T>, OptionQuery>;
 
+	/// The price of minting a kitty, overriding `KittyPrice` when set.
+	#[pallet::storage]
+	#[pallet::getter(fn mint_price)]
+	pub type MintPrice<T: Config> = StorageValue<_, BalanceOf<T>, OptionQuery>;
+
+	/// The call groups that are currently disabled.
+	#[pallet::storage]
+	#[pallet::getter(fn is_paused)]
+	pub type PausedCallGroups<T> = StorageMap<_, Blake2_128Concat, CallGroup, bool, ValueQuery>;
+
+	/// The rarest kitties as `(kitty_id, rarity)`, rarest first, on a best-effort basis: kitties
+	/// only enter the board when they are minted or bred. A burned kitty leaves a gap rather than
+	/// making room for the next rarest one, so kitties pushed off the board before stay off, and
+	/// it holds fewer than `LeaderboardSize` kitties until new ones qualify.
+	#[pallet::storage]
+	#[pallet::getter(fn leaderboard)]
+	pub type Leaderboard<T: Config> =
+		StorageValue<_, BoundedVec<(KittyId, Rarity), T::LeaderboardSize>, ValueQuery>;
+
+	/// The kitties that are staked or unbonding.
+	#[pallet::storage]
+	#[pallet::getter(fn stakes)]
+	pub type Stakes<T: Config> =
+		StorageMap<_, Blake2_128Concat, KittyId, StakeInfo<BlockNumberFor<T>>, OptionQuery>;
+
+	/// The digest of the latest sale, under which it is recorded in the offchain database.
+	#[pallet::storage]
+	#[pallet::getter(fn last_sale)]
+	pub type LastSale<T: Config> = StorageValue<_, T::Hash, OptionQuery>;
+
+	/// The digest of the latest sale of each kitty.
+	#[pallet::storage]
+	#[pallet::getter(fn last_kitty_sale)]
+	pub type LastKittySale<T: Config> =
+		StorageMap<_, Blake2_128Concat, KittyId, T::Hash, OptionQuery>;
+
+	/// The digest of the latest sale each account bought or sold in.
+	#[pallet::storage]
+	#[pallet::getter(fn last_account_sale)]
+	pub type LastAccountSale<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::Hash, OptionQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Kitties created at genesis, as `(owner, name, price)`; a `price` puts the kitty on sale.
+		pub kitties: Vec<(T::AccountId, [u8; 8], Option<BalanceOf<T>>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { kitties: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (owner, name, price) in &self.kitties {
+				let kitty_id = Pallet::<T>::get_next_id().expect("too many genesis kitties");
+				// There is no randomness yet, so the DNA is derived from the kitty itself.
+				let dna = (kitty_id, owner, name).using_encoded(blake2_128);
+
+				let rarity = rarity_score(&dna, 0);
+
+				Kitties::<T>::insert(kitty_id, Kitty { dna, name: *name, generation: 0, rarity });
+				KittyOwner::<T>::insert(kitty_id, owner);
+				Pallet::<T>::enter_leaderboard(kitty_id, rarity);
+				if let Some(price) = price {
+					KittyPrices::<T>::insert(kitty_id, price);
+				}
+			}
+		}
+	}
 
 	// Pallets use events to inform users when important changes are made.
 	#[pallet::event]
@@ -82,6 +197,15 @@ pub mod pallet {
 		KittyTransferred { from: T::AccountId, to: T::AccountId, kitty_id: KittyId},
 		KittyForSale { who: T::AccountId, kitty_id: KittyId, price: BalanceOf<T>},
 		KittySold { from: T::AccountId, to: T::AccountId, kitty_id: KittyId, price: BalanceOf<T>},
+		KittyBurned { owner: T::AccountId, kitty_id: KittyId },
+		/// The mint price was changed; `None` means `KittyPrice` applies again.
+		MintPriceSet { price: Option<BalanceOf<T>> },
+		CallGroupPauseSet { group: CallGroup, paused: bool },
+		KittyStaked { who: T::AccountId, kitty_id: KittyId },
+		/// The kitty stops earning rewards and is released once `unbonding_until` is reached.
+		KittyUnstaked { who: T::AccountId, kitty_id: KittyId, unbonding_until: BlockNumberFor<T> },
+		RewardsClaimed { who: T::AccountId, kitty_id: KittyId, amount: BalanceOf<T> },
+		KittyReleased { who: T::AccountId, kitty_id: KittyId },
 	}
 
 	// Errors inform users that something went wrong.
@@ -94,12 +218,43 @@ pub mod pallet {
 		AlreadyOnSale,
 		NotOnSale,
 		InvalidPrice,
+		MintingPaused,
+		TransfersPaused,
+		TradingPaused,
+		StakingPaused,
+		/// The kitty is staked or unbonding.
+		KittyStaked,
+		NotStaked,
+		AlreadyUnstaked,
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_runtime_upgrade() -> Weight {
-			migrations::v2::migrate::<T>()
+			let on_chain_version = Self::on_chain_storage_version();
+			if on_chain_version < 2 {
+				// The old v2 migration widened names without bumping the version, so a chain on
+				// version 1 may hold either layout and cannot be migrated safely.
+				frame_support::log::error!(
+					"pallet_kitties: storage version {:?} predates the supported migrations",
+					on_chain_version,
+				);
+				return T::DbWeight::get().reads(1)
+			}
+			migrations::v3::migrate::<T>().saturating_add(migrations::v4::migrate::<T>())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			ensure!(
+				Self::on_chain_storage_version() >= 2,
+				"pallet_kitties: storage versions before 2 cannot be migrated"
+			);
+			Ok(Vec::new())
+		}
+
+		fn offchain_worker(block_number: BlockNumberFor<T>) {
+			crate::offchain::index_events::<T>(block_number);
 		}
 	}
 
@@ -113,17 +268,19 @@ pub mod pallet {
 		#[pallet::weight(10_000)]
 		pub fn create(origin: OriginFor<T>, name: [u8; 8]) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallGroup::Minting)?;
 
 			let kitty_id = Self::get_next_id()?;
 			let dna = Self::random_value(&who);
-			let kitty = Kitty { dna, name };
+			let kitty = Kitty { dna, name, generation: 0, rarity: rarity_score(&dna, 0) };
 
-			let price = T::KittyPrice::get();
+			let price = Self::current_mint_price();
 			// T::Currency::reserve(&who, price)?;
 			T::Currency::transfer(&who, &Self::get_account_id(), price, ExistenceRequirement::KeepAlive)?;
 
 			Kitties::<T>::insert(kitty_id, &kitty);
 			KittyOwner::<T>::insert(kitty_id, &who);
+			Self::enter_leaderboard(kitty_id, kitty.rarity);
 
 			// Emit an event.
 			Self::deposit_event(Event::KittyCreated {who, kitty_id, kitty });
@@ -136,11 +293,13 @@ pub mod pallet {
 		#[pallet::weight(10_000)]
 		pub fn transfer(origin: OriginFor<T>, to: T::AccountId, kitty_id: KittyId) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallGroup::Transfers)?;
 
 			ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyId);
 
 			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
 			ensure!(owner == who, Error::<T>::NotOwner);
+			Self::ensure_not_staked(kitty_id)?;
 
 			KittyOwner::<T>::insert(kitty_id, &to);
 			Self::deposit_event(Event::KittyTransferred {from: who, to, kitty_id});
@@ -151,11 +310,14 @@ pub mod pallet {
 		#[pallet::weight(10_000)]
 		pub fn breed(origin: OriginFor<T>, kitty_id_1: KittyId, kitty_id_2: KittyId, name: [u8; 8]) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallGroup::Minting)?;
 
 			ensure!(kitty_id_1 != kitty_id_2, Error::<T>::SameParentsId);
 
 			ensure!(Kitties::<T>::contains_key(kitty_id_1), Error::<T>::InvalidKittyId);
 			ensure!(Kitties::<T>::contains_key(kitty_id_2), Error::<T>::InvalidKittyId);
+			Self::ensure_not_staked(kitty_id_1)?;
+			Self::ensure_not_staked(kitty_id_2)?;
 
 			let kitty_id = Self::get_next_id()?;
 			let kitty_1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
@@ -166,15 +328,19 @@ pub mod pallet {
 			for i in 0..kitty_1.dna.len() {
 				dna[i] = (kitty_1.dna[i] & selector[i]) | (kitty_2.dna[i] & !selector[i]);
 			}
-			let kitty = Kitty{dna, name};
+			let generation = kitty_1.generation.max(kitty_2.generation).saturating_add(1);
+			let kitty = Kitty{dna, name, generation, rarity: rarity_score(&dna, generation)};
 
-			let price = T::KittyPrice::get();
+			let price = Self::current_mint_price();
 			// T::Currency::reserve(&who, price)?;
 			T::Currency::transfer(&who, &Self::get_account_id(), price, ExistenceRequirement::KeepAlive)?;
 
 			Kitties::<T>::insert(kitty_id, &kitty);
 			KittyOwner::<T>::insert(kitty_id, &who);
 			KittyParents::<T>::insert(kitty_id, (kitty_id_1, kitty_id_2));
+			KittyChildren::<T>::insert(kitty_id_1, kitty_id, ());
+			KittyChildren::<T>::insert(kitty_id_2, kitty_id, ());
+			Self::enter_leaderboard(kitty_id, kitty.rarity);
 
 			// Emit an event.
 			Self::deposit_event(Event::KittyBred {who, kitty_id, kitty });
@@ -186,6 +352,7 @@ pub mod pallet {
 		#[pallet::weight(10_000)]
 		pub fn set_price(origin: OriginFor<T>, kitty_id: KittyId, price: BalanceOf<T>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallGroup::Trading)?;
 
 			ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyId);
 
@@ -193,6 +360,7 @@ pub mod pallet {
 			ensure!(owner == who, Error::<T>::NotOwner);
 
 			ensure!(!KittyPrices::<T>::contains_key(kitty_id), Error::<T>::AlreadyOnSale);
+			Self::ensure_not_staked(kitty_id)?;
 
 			KittyPrices::<T>::insert(kitty_id, price);
 			Self::deposit_event(Event::KittyForSale {who, kitty_id, price});
@@ -204,6 +372,7 @@ pub mod pallet {
 		#[pallet::weight(10_000)]
 		pub fn buy(origin: OriginFor<T>, kitty_id: KittyId) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallGroup::Trading)?;
 
 			ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyId);
 
@@ -220,10 +389,153 @@ pub mod pallet {
 			KittyOwner::<T>::insert(kitty_id, &who);
 			KittyPrices::<T>::remove(kitty_id);
 
+			crate::offchain::index_sale::<T>(kitty_id, &owner, &who, price);
+
 			Self::deposit_event(Event::KittySold {from: owner, to: who, kitty_id, price});
 
 			Ok(())
 		}
+
+		/// Move a kitty to `to` regardless of its owner, cancelling any sale or stake. Unpaid
+		/// staking rewards are forfeited.
+		#[pallet::call_index(5)]
+		#[pallet::weight(10_000)]
+		pub fn force_transfer(origin: OriginFor<T>, to: T::AccountId, kitty_id: KittyId) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+
+			KittyOwner::<T>::insert(kitty_id, &to);
+			KittyPrices::<T>::remove(kitty_id);
+			Stakes::<T>::remove(kitty_id);
+			Self::deposit_event(Event::KittyTransferred {from: owner, to, kitty_id});
+			Ok(())
+		}
+
+		/// Destroy a kitty. Its parents are kept so the lineage of its offspring stays intact.
+		#[pallet::call_index(6)]
+		#[pallet::weight(10_000)]
+		pub fn force_burn(origin: OriginFor<T>, kitty_id: KittyId) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			let owner = KittyOwner::<T>::take(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+
+			Kitties::<T>::remove(kitty_id);
+			KittyPrices::<T>::remove(kitty_id);
+			Stakes::<T>::remove(kitty_id);
+			Self::leave_leaderboard(kitty_id);
+			Self::deposit_event(Event::KittyBurned {owner, kitty_id});
+			Ok(())
+		}
+
+		/// Set the price of minting a kitty, or `None` to fall back to `KittyPrice`.
+		#[pallet::call_index(7)]
+		#[pallet::weight(10_000)]
+		pub fn set_mint_price(origin: OriginFor<T>, price: Option<BalanceOf<T>>) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			MintPrice::<T>::set(price);
+			Self::deposit_event(Event::MintPriceSet {price});
+			Ok(())
+		}
+
+		/// Disable or re-enable `create` and `breed`.
+		#[pallet::call_index(8)]
+		#[pallet::weight(10_000)]
+		pub fn pause_minting(origin: OriginFor<T>, paused: bool) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			Self::do_set_paused(CallGroup::Minting, paused);
+			Ok(())
+		}
+
+		/// Disable or re-enable a group of calls, e.g. to halt trading while a bug is fixed.
+		/// Calls under `ForceOrigin` are never paused.
+		#[pallet::call_index(9)]
+		#[pallet::weight(10_000)]
+		pub fn set_call_group_paused(origin: OriginFor<T>, group: CallGroup, paused: bool) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			Self::do_set_paused(group, paused);
+			Ok(())
+		}
+
+		/// Lock a kitty to earn rewards from the pallet account.
+		#[pallet::call_index(10)]
+		#[pallet::weight(10_000)]
+		pub fn stake(origin: OriginFor<T>, kitty_id: KittyId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallGroup::Staking)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(owner == who, Error::<T>::NotOwner);
+			ensure!(!KittyPrices::<T>::contains_key(kitty_id), Error::<T>::AlreadyOnSale);
+			Self::ensure_not_staked(kitty_id)?;
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			Stakes::<T>::insert(kitty_id, StakeInfo {rewarded_until: now, unbonding_since: None});
+			Self::deposit_event(Event::KittyStaked {who, kitty_id});
+			Ok(())
+		}
+
+		/// Stop earning rewards and start the unbonding period of a staked kitty.
+		#[pallet::call_index(11)]
+		#[pallet::weight(10_000)]
+		pub fn unstake(origin: OriginFor<T>, kitty_id: KittyId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallGroup::Staking)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(owner == who, Error::<T>::NotOwner);
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			Stakes::<T>::try_mutate(kitty_id, |stake| -> DispatchResult {
+				let stake = stake.as_mut().ok_or(Error::<T>::NotStaked)?;
+				ensure!(stake.unbonding_since.is_none(), Error::<T>::AlreadyUnstaked);
+				stake.unbonding_since = Some(now);
+				Ok(())
+			})?;
+
+			let unbonding_until = now.saturating_add(T::UnbondingPeriod::get());
+			Self::deposit_event(Event::KittyUnstaked {who, kitty_id, unbonding_until});
+			Ok(())
+		}
+
+		/// Pay out the rewards of a staked kitty. Once its unbonding period is over, this also
+		/// releases the kitty, paying only what the pallet account can spare and forfeiting the
+		/// rest, so an underfunded account cannot keep the kitty locked.
+		#[pallet::call_index(12)]
+		#[pallet::weight(10_000)]
+		pub fn claim_rewards(origin: OriginFor<T>, kitty_id: KittyId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallGroup::Staking)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(owner == who, Error::<T>::NotOwner);
+			let mut stake = Self::stakes(kitty_id).ok_or(Error::<T>::NotStaked)?;
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			let released = matches!(stake.unbonding_since, Some(since) if Self::unbonding_over(since));
+			let pallet_account = Self::get_account_id();
+			let mut amount = Self::pending_rewards(kitty_id);
+			if released {
+				let spare = T::Currency::free_balance(&pallet_account).saturating_sub(T::Currency::minimum_balance());
+				amount = amount.min(spare);
+			}
+			if !amount.is_zero() {
+				T::Currency::transfer(&pallet_account, &who, amount, ExistenceRequirement::KeepAlive)?;
+				Self::deposit_event(Event::RewardsClaimed {who: who.clone(), kitty_id, amount});
+			}
+
+			if released {
+				Stakes::<T>::remove(kitty_id);
+				Self::deposit_event(Event::KittyReleased {who, kitty_id});
+			} else {
+				stake.rewarded_until = stake.rewards_end(now);
+				Stakes::<T>::insert(kitty_id, stake);
+			}
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -244,6 +556,32 @@ pub mod pallet {
 			payload.using_encoded(blake2_128)
 		}
 
+		/// The price of minting a kitty: `MintPrice` if set, `KittyPrice` otherwise.
+		pub fn current_mint_price() -> BalanceOf<T> {
+			Self::mint_price().unwrap_or_else(T::KittyPrice::get)
+		}
+
+		fn ensure_not_paused(group: CallGroup) -> DispatchResult {
+			if !Self::is_paused(group) {
+				return Ok(());
+			}
+			Err(match group {
+				CallGroup::Minting => Error::<T>::MintingPaused,
+				CallGroup::Transfers => Error::<T>::TransfersPaused,
+				CallGroup::Trading => Error::<T>::TradingPaused,
+				CallGroup::Staking => Error::<T>::StakingPaused,
+			}.into())
+		}
+
+		fn do_set_paused(group: CallGroup, paused: bool) {
+			if paused {
+				PausedCallGroups::<T>::insert(group, true);
+			} else {
+				PausedCallGroups::<T>::remove(group);
+			}
+			Self::deposit_event(Event::CallGroupPauseSet {group, paused});
+		}
+
 		fn get_account_id() -> T::AccountId {
 			T::PalletId::get().into_account_truncating()
 		}
@@ -0,0 +1,145 @@
+//! Family trees of kitties.
+//!
+//! A kitty always has a higher id than its parents, which the kinship computation relies on to
+//! tell an ancestor from a descendant.
+
+use crate::{Config, KittyChildren, KittyId, Pallet};
+use codec::{Decode, Encode};
+use frame_support::{
+	sp_std::{
+		collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+		prelude::*,
+	},
+	RuntimeDebug,
+};
+use scale_info::TypeInfo;
+use sp_runtime::Perbill;
+
+/// How many generations lineage queries look at, at most.
+pub const MAX_LINEAGE_DEPTH: u32 = 16;
+
+/// A kitty in a family tree.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct FamilyMember {
+	pub kitty_id: KittyId,
+	/// How many generations away the kitty is from the kitty the tree was built for.
+	pub generation: u32,
+	/// The parents of the kitty, if it was bred.
+	pub parents: Option<(KittyId, KittyId)>,
+}
+
+impl<T: Config> Pallet<T> {
+	/// The kitties bred from `kitty_id`, by id.
+	pub fn children(kitty_id: KittyId) -> Vec<KittyId> {
+		let mut children: Vec<_> = KittyChildren::<T>::iter_key_prefix(kitty_id).collect();
+		children.sort_unstable();
+		children
+	}
+
+	/// `kitty_id` and its ancestors up to `depth` generations back. An ancestor reached through
+	/// several lines is listed once, at its closest generation. Empty if the kitty was never
+	/// created.
+	pub fn ancestry(kitty_id: KittyId, depth: u32) -> Vec<FamilyMember> {
+		Self::family_tree(kitty_id, depth, |kitty_id| {
+			Self::kitty_parents(kitty_id).map_or_else(Vec::new, |(a, b)| vec![a, b])
+		})
+	}
+
+	/// `kitty_id` and its descendants up to `depth` generations down, listed like in
+	/// [`Self::ancestry`].
+	pub fn descendants(kitty_id: KittyId, depth: u32) -> Vec<FamilyMember> {
+		Self::family_tree(kitty_id, depth, Self::children)
+	}
+
+	/// Wright's inbreeding coefficient of `kitty_id`: the probability that both copies of a gene
+	/// come from the same ancestor. Only ancestors up to `MAX_LINEAGE_DEPTH` generations back
+	/// from the parents are taken into account.
+	pub fn inbreeding_coefficient(kitty_id: KittyId) -> Perbill {
+		match Self::kitty_parents(kitty_id) {
+			Some((parent_1, parent_2)) =>
+				Self::offspring_inbreeding_coefficient(parent_1, parent_2),
+			None => Perbill::zero(),
+		}
+	}
+
+	/// The inbreeding coefficient a kitty bred from `parent_1` and `parent_2` would have.
+	pub fn offspring_inbreeding_coefficient(parent_1: KittyId, parent_2: KittyId) -> Perbill {
+		let parents = Self::ancestry(parent_1, MAX_LINEAGE_DEPTH)
+			.into_iter()
+			.chain(Self::ancestry(parent_2, MAX_LINEAGE_DEPTH))
+			.filter_map(|member| Some((member.kitty_id, member.parents?)))
+			.collect();
+
+		Kinship { parents, memo: BTreeMap::new() }.of(parent_1, parent_2)
+	}
+
+	/// Walk the family of `kitty_id` breadth first, `next` giving the relatives one generation
+	/// further away.
+	fn family_tree(
+		kitty_id: KittyId,
+		depth: u32,
+		next: impl Fn(KittyId) -> Vec<KittyId>,
+	) -> Vec<FamilyMember> {
+		if kitty_id >= Self::next_kitty_id() {
+			return Vec::new()
+		}
+
+		let depth = depth.min(MAX_LINEAGE_DEPTH);
+		let mut members =
+			vec![FamilyMember { kitty_id, generation: 0, parents: Self::kitty_parents(kitty_id) }];
+		let mut seen = BTreeSet::new();
+		seen.insert(kitty_id);
+
+		let mut index = 0;
+		while index < members.len() {
+			let (kitty_id, generation) = (members[index].kitty_id, members[index].generation + 1);
+			index += 1;
+			if generation > depth {
+				break
+			}
+			for relative in next(kitty_id) {
+				if seen.insert(relative) {
+					let parents = Self::kitty_parents(relative);
+					members.push(FamilyMember { kitty_id: relative, generation, parents });
+				}
+			}
+		}
+
+		members
+	}
+}
+
+/// Kinship coefficients within a known set of parents. Kitties without known parents are treated
+/// as unrelated founders.
+struct Kinship {
+	parents: BTreeMap<KittyId, (KittyId, KittyId)>,
+	memo: BTreeMap<(KittyId, KittyId), Perbill>,
+}
+
+impl Kinship {
+	/// The probability that a gene picked from `a` and one picked from `b` are copies of the same
+	/// ancestral gene.
+	fn of(&mut self, a: KittyId, b: KittyId) -> Perbill {
+		// With `a` the younger kitty, `a` cannot be an ancestor of `b`.
+		let (a, b) = if a >= b { (a, b) } else { (b, a) };
+		if let Some(kinship) = self.memo.get(&(a, b)) {
+			return *kinship
+		}
+
+		let kinship = match self.parents.get(&a).copied() {
+			// Two picks from the same kitty hit the same gene half of the time, and otherwise
+			// share one as often as the kitty is inbred.
+			Some((parent_1, parent_2)) if a == b =>
+				half_of_sum(Perbill::one(), self.of(parent_1, parent_2)),
+			None if a == b => Perbill::from_percent(50),
+			Some((parent_1, parent_2)) => half_of_sum(self.of(parent_1, b), self.of(parent_2, b)),
+			None => Perbill::zero(),
+		};
+		self.memo.insert((a, b), kinship);
+		kinship
+	}
+}
+
+fn half_of_sum(x: Perbill, y: Perbill) -> Perbill {
+	Perbill::from_parts(((x.deconstruct() as u64 + y.deconstruct() as u64) / 2) as u32)
+}
@@ -0,0 +1,31 @@
+use crate::{Config, KittyChildren, KittyParents, Pallet};
+use frame_support::{
+    pallet_prelude::*,
+    traits::GetStorageVersion,
+    weights::Weight,
+};
+
+/// Fill `KittyChildren` from the parents of the kitties bred so far.
+pub fn migrate<T: Config>() -> Weight {
+    let on_chain_version = Pallet::<T>::on_chain_storage_version();
+    let current_version = Pallet::<T>::current_storage_version();
+
+    if on_chain_version != 2 {
+        return Weight::zero();
+    }
+
+    if current_version < 3 {
+        return Weight::zero();
+    }
+
+    let mut bred = 0;
+    for (kitty_id, (parent_1, parent_2)) in KittyParents::<T>::iter() {
+        KittyChildren::<T>::insert(parent_1, kitty_id, ());
+        KittyChildren::<T>::insert(parent_2, kitty_id, ());
+        bred += 1;
+    }
+
+    StorageVersion::new(3).put::<Pallet<T>>();
+
+    T::DbWeight::get().reads_writes(bred + 1, bred * 2 + 1)
+}
@@ -0,0 +1,61 @@
+use crate::{
+    rarity_score, Config, Kitties, Kitty, KittyId, KittyParents, Leaderboard, NextKittyId, Pallet,
+};
+use frame_support::{
+    pallet_prelude::*,
+    sp_std::prelude::*,
+    traits::GetStorageVersion,
+    weights::Weight,
+};
+
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default, TypeInfo, MaxEncodedLen)]
+pub struct OldKitty {
+    pub dna: [u8; 16],
+    pub name: [u8; 8],
+}
+
+/// Give every kitty its generation and rarity, and fill the leaderboard.
+pub fn migrate<T: Config>() -> Weight {
+    let on_chain_version = Pallet::<T>::on_chain_storage_version();
+    let current_version = Pallet::<T>::current_storage_version();
+
+    if on_chain_version != 3 {
+        return Weight::zero();
+    }
+
+    if current_version < 4 {
+        return Weight::zero();
+    }
+
+    // Parents have lower ids than their offspring, so their generation is known first. Burned
+    // kitties keep their parents and count too.
+    let next_kitty_id = NextKittyId::<T>::get();
+    let mut generations = Vec::with_capacity(next_kitty_id as usize);
+    for kitty_id in 0..next_kitty_id {
+        let generation = match KittyParents::<T>::get(kitty_id) {
+            Some((parent_1, parent_2)) => {
+                let generation_of =
+                    |kitty_id: KittyId| generations.get(kitty_id as usize).copied().unwrap_or(0u32);
+                generation_of(parent_1).max(generation_of(parent_2)).saturating_add(1)
+            },
+            None => 0,
+        };
+        generations.push(generation);
+    }
+
+    let mut board = Vec::new();
+    Kitties::<T>::translate::<OldKitty, _>(|kitty_id: KittyId, kitty| {
+        let generation = generations.get(kitty_id as usize).copied().unwrap_or_default();
+        let rarity = rarity_score(&kitty.dna, generation);
+        board.push((kitty_id, rarity));
+        Some(Kitty { dna: kitty.dna, name: kitty.name, generation, rarity })
+    });
+
+    let migrated = board.len() as u64;
+    board.sort_by(|(id_1, rarity_1), (id_2, rarity_2)| rarity_2.cmp(rarity_1).then(id_1.cmp(id_2)));
+    Leaderboard::<T>::put(BoundedVec::truncate_from(board));
+
+    StorageVersion::new(4).put::<Pallet<T>>();
+
+    T::DbWeight::get().reads_writes(next_kitty_id as u64 + migrated + 1, migrated + 2)
+}
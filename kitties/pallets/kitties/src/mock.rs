@@ -87,6 +87,10 @@ impl pallet_kitties::Config for Test {
 	type Currency = Balances;
 	type KittyPrice = KittyPrice;
 	type PalletId = KittyPalletId; 
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type LeaderboardSize = ConstU32<3>;
+	type StakingReward = ConstU128<1>;
+	type UnbondingPeriod = ConstU64<10>;
 }
 
 impl pallet_insecure_randomness_collective_flip::Config for Test {}
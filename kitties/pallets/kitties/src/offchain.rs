@@ -0,0 +1,162 @@
+//! Indexes of kitty ownership and sales in the offchain database.
+//!
+//! The owner index is kept in offchain local storage by the offchain worker, and is best effort.
+//! The worker does not run for blocks imported during a major sync, nor without
+//! `--offchain-worker`, so changes of owner in those blocks are missed and the index keeps the
+//! previous owner until the kitty changes hands again. Kitties created at genesis are not indexed
+//! until they change hands. The chain state is the authority on who owns a kitty.
+//!
+//! Sales are written by `buy` through offchain indexing, which the node only persists with
+//! `--enable-offchain-indexing`. Each is stored under the digest of its record, and links to the
+//! previous sale overall, of the same kitty and of each party. The latest digests are kept on
+//! chain, so walking back from the state of a block only reaches the sales of its own chain,
+//! while those of retracted blocks stay behind unreferenced.
+
+use crate::{BalanceOf, Config, Event, KittyId, LastAccountSale, LastKittySale, LastSale};
+use codec::{Decode, Encode};
+use frame_support::{sp_std::prelude::*, RuntimeDebug};
+use frame_system::pallet_prelude::BlockNumberFor;
+use scale_info::TypeInfo;
+use sp_runtime::{
+	offchain::{
+		storage::StorageValueRef,
+		storage_lock::{StorageLock, Time},
+	},
+	traits::Hash,
+};
+
+const OWNED_PREFIX: &[u8] = b"kitties::index::owned";
+const OWNER_PREFIX: &[u8] = b"kitties::index::owner";
+const SALE_PREFIX: &[u8] = b"kitties::index::sale";
+const LOCK_KEY: &[u8] = b"kitties::index::lock";
+
+/// A sale recorded in the offchain database.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct SaleRecord<AccountId, Balance, BlockNumber, Hash> {
+	pub kitty_id: KittyId,
+	pub seller: AccountId,
+	pub buyer: AccountId,
+	pub price: Balance,
+	pub block_number: BlockNumber,
+	/// The previous sale of any kitty.
+	pub previous: Option<Hash>,
+	/// The previous sale of the same kitty.
+	pub previous_of_kitty: Option<Hash>,
+	/// The previous sale the seller bought or sold in.
+	pub previous_of_seller: Option<Hash>,
+	/// The previous sale the buyer bought or sold in.
+	pub previous_of_buyer: Option<Hash>,
+}
+
+pub type SaleRecordOf<T> = SaleRecord<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	BlockNumberFor<T>,
+	<T as frame_system::Config>::Hash,
+>;
+
+/// The key of the kitty ids owned by `who`, stored as a `Vec<KittyId>`.
+pub fn owned_kitties_key<AccountId: Encode>(who: &AccountId) -> Vec<u8> {
+	(OWNED_PREFIX, who).encode()
+}
+
+/// The key of the sale with the given digest, stored as a `SaleRecord`.
+pub fn sale_key<Hash: Encode>(digest: &Hash) -> Vec<u8> {
+	(SALE_PREFIX, digest).encode()
+}
+
+/// The key of the owner of `kitty_id` as last indexed, with the block it was indexed at.
+fn owner_key(kitty_id: KittyId) -> Vec<u8> {
+	(OWNER_PREFIX, kitty_id).encode()
+}
+
+/// Index the kitty events deposited in the current block.
+pub fn index_events<T: Config>(block_number: BlockNumberFor<T>) {
+	// Workers of consecutive blocks can run at the same time.
+	let mut lock = StorageLock::<Time>::new(LOCK_KEY);
+	let _guard = lock.lock();
+
+	for record in frame_system::Pallet::<T>::read_events_no_consensus() {
+		let event: <T as Config>::RuntimeEvent = record.event.into();
+		let event: Event<T> = match event.try_into() {
+			Ok(event) => event,
+			Err(_) => continue,
+		};
+
+		match event {
+			Event::KittyCreated { who, kitty_id, .. } | Event::KittyBred { who, kitty_id, .. } =>
+				set_owner::<T>(kitty_id, Some(who), block_number),
+			Event::KittyTransferred { to, kitty_id, .. } | Event::KittySold { to, kitty_id, .. } =>
+				set_owner::<T>(kitty_id, Some(to), block_number),
+			Event::KittyBurned { kitty_id, .. } => set_owner::<T>(kitty_id, None, block_number),
+			_ => {},
+		}
+	}
+}
+
+/// Move `kitty_id` to `owner` in the index, unless a later block already moved it.
+fn set_owner<T: Config>(
+	kitty_id: KittyId,
+	owner: Option<T::AccountId>,
+	block_number: BlockNumberFor<T>,
+) {
+	let owner_ref = StorageValueRef::persistent(&owner_key(kitty_id));
+	let previous = owner_ref.get::<(Option<T::AccountId>, BlockNumberFor<T>)>().ok().flatten();
+
+	let previous_owner = match previous {
+		Some((_, indexed_at)) if indexed_at > block_number => return,
+		Some((previous_owner, _)) => previous_owner,
+		None => None,
+	};
+
+	if let Some(previous_owner) = previous_owner {
+		update_owned::<T>(&previous_owner, |owned| owned.retain(|id| *id != kitty_id));
+	}
+	if let Some(owner) = &owner {
+		update_owned::<T>(owner, |owned| {
+			if !owned.contains(&kitty_id) {
+				owned.push(kitty_id);
+			}
+		});
+	}
+	owner_ref.set(&(owner, block_number));
+}
+
+fn update_owned<T: Config>(who: &T::AccountId, f: impl FnOnce(&mut Vec<KittyId>)) {
+	let mut owned_ref = StorageValueRef::persistent(&owned_kitties_key(who));
+	let mut owned = owned_ref.get::<Vec<KittyId>>().ok().flatten().unwrap_or_default();
+	f(&mut owned);
+	if owned.is_empty() {
+		owned_ref.clear();
+	} else {
+		owned_ref.set(&owned);
+	}
+}
+
+/// Record a sale in the offchain database, from within block execution.
+pub(crate) fn index_sale<T: Config>(
+	kitty_id: KittyId,
+	seller: &T::AccountId,
+	buyer: &T::AccountId,
+	price: BalanceOf<T>,
+) {
+	let sale: SaleRecordOf<T> = SaleRecord {
+		kitty_id,
+		seller: seller.clone(),
+		buyer: buyer.clone(),
+		price,
+		block_number: frame_system::Pallet::<T>::block_number(),
+		previous: LastSale::<T>::get(),
+		previous_of_kitty: LastKittySale::<T>::get(kitty_id),
+		previous_of_seller: LastAccountSale::<T>::get(seller),
+		previous_of_buyer: LastAccountSale::<T>::get(buyer),
+	};
+	// The links make the digest unique to the chain of sales leading up to this one.
+	let digest = T::Hashing::hash_of(&sale);
+	sp_io::offchain_index::set(&sale_key(&digest), &sale.encode());
+
+	LastSale::<T>::put(digest);
+	LastKittySale::<T>::insert(kitty_id, digest);
+	LastAccountSale::<T>::insert(seller, digest);
+	LastAccountSale::<T>::insert(buyer, digest);
+}
@@ -0,0 +1,48 @@
+//! Rarity scores of kitties and a best-effort leaderboard of the rarest ones.
+
+use crate::{Config, KittyId, Leaderboard, Pallet};
+use frame_support::{sp_std::prelude::*, traits::Get, BoundedVec};
+
+/// How rare a kitty is; higher is rarer.
+pub type Rarity = u32;
+
+/// The score a kitty gains for each generation it is bred away from the founders.
+pub const GENERATION_RARITY: Rarity = 10;
+
+/// The number of bytes with `n` set bits, out of all 256 bytes.
+const BYTES_WITH_SET_BITS: [u32; 9] = [1, 8, 28, 56, 70, 56, 28, 8, 1];
+
+/// The rarity score of a kitty with the given DNA and generation.
+///
+/// Each byte of DNA scores how unlikely a random byte is to have as many set bits, from 3 for
+/// four set bits to 256 for none or all of them.
+pub fn rarity_score(dna: &[u8; 16], generation: u32) -> Rarity {
+	let genes: Rarity = dna
+		.iter()
+		.map(|gene| 256 / BYTES_WITH_SET_BITS[gene.count_ones() as usize])
+		.sum();
+	genes.saturating_add(generation.saturating_mul(GENERATION_RARITY))
+}
+
+impl<T: Config> Pallet<T> {
+	/// Put the new `kitty_id` on the leaderboard if there is room or it is rarer than a kitty on it.
+	pub(crate) fn enter_leaderboard(kitty_id: KittyId, rarity: Rarity) {
+		Leaderboard::<T>::mutate(|board| {
+			// Older kitties keep their place over equally rare newer ones.
+			let index = board.iter().position(|(_, other)| *other < rarity).unwrap_or(board.len());
+			if index >= T::LeaderboardSize::get() as usize {
+				return
+			}
+
+			let mut entries = board.to_vec();
+			entries.insert(index, (kitty_id, rarity));
+			*board = BoundedVec::truncate_from(entries);
+		});
+	}
+
+	/// Take `kitty_id` off the leaderboard. The kitties it pushed off are not put back, as finding
+	/// them would mean going through every kitty.
+	pub(crate) fn leave_leaderboard(kitty_id: KittyId) {
+		Leaderboard::<T>::mutate(|board| board.retain(|(other, _)| *other != kitty_id));
+	}
+}
@@ -0,0 +1,62 @@
+//! Staking kitties for rewards paid from the pallet account.
+//!
+//! A staked kitty earns `StakingReward` per block for each point of its rarity and cannot be
+//! transferred, put on sale or bred. `unstake` stops the rewards, and the kitty stays locked for
+//! `UnbondingPeriod` blocks before `claim_rewards` releases it.
+
+use crate::{BalanceOf, Config, Error, KittyId, Pallet, Stakes};
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{dispatch::DispatchResult, ensure, traits::Get, RuntimeDebug};
+use frame_system::pallet_prelude::BlockNumberFor;
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	SaturatedConversion,
+};
+
+/// The staking state of a kitty.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct StakeInfo<BlockNumber> {
+	/// The block up to which rewards have been paid out.
+	pub rewarded_until: BlockNumber,
+	/// The block the kitty was unstaked at, if it was.
+	pub unbonding_since: Option<BlockNumber>,
+}
+
+impl<BlockNumber: Copy> StakeInfo<BlockNumber> {
+	/// The block rewards stop accruing at, `now` while the kitty is still staked.
+	pub fn rewards_end(&self, now: BlockNumber) -> BlockNumber {
+		self.unbonding_since.unwrap_or(now)
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The rewards `kitty_id` has earned and not been paid yet.
+	pub fn pending_rewards(kitty_id: KittyId) -> BalanceOf<T> {
+		let (stake, kitty) = match (Self::stakes(kitty_id), Self::kitties(kitty_id)) {
+			(Some(stake), Some(kitty)) => (stake, kitty),
+			_ => return Zero::zero(),
+		};
+
+		let now = frame_system::Pallet::<T>::block_number();
+		let blocks = stake.rewards_end(now).saturating_sub(stake.rewarded_until);
+		T::StakingReward::get()
+			.saturating_mul(kitty.rarity.into())
+			.saturating_mul(blocks.saturated_into::<u32>().into())
+	}
+
+	/// Whether `kitty_id` is staked or unbonding.
+	pub fn is_staked(kitty_id: KittyId) -> bool {
+		Stakes::<T>::contains_key(kitty_id)
+	}
+
+	pub(crate) fn ensure_not_staked(kitty_id: KittyId) -> DispatchResult {
+		ensure!(!Self::is_staked(kitty_id), Error::<T>::KittyStaked);
+		Ok(())
+	}
+
+	/// Whether the unbonding period of a kitty unstaked at `since` is over.
+	pub(crate) fn unbonding_over(since: BlockNumberFor<T>) -> bool {
+		frame_system::Pallet::<T>::block_number() >= since.saturating_add(T::UnbondingPeriod::get())
+	}
+}
@@ -1,5 +1,21 @@
-use crate::{mock::*, Error, Event};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	mock::*,
+	offchain::{owned_kitties_key, sale_key, SaleRecord},
+	migrations::v4::OldKitty,
+	rarity::GENERATION_RARITY,
+	rarity_score, CallGroup, Error, Event, FamilyMember, Kitties, KittyId, Leaderboard,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::unhashed,
+	traits::{GenesisBuild, GetStorageVersion, Hooks, StorageVersion},
+};
+use sp_core::{
+	offchain::{testing::TestOffchainExt, OffchainDbExt, OffchainWorkerExt},
+	H256,
+};
+use sp_runtime::{offchain::storage::StorageValueRef, traits::AccountIdConversion, Perbill};
 
 #[test]
 fn create_works() {
@@ -85,6 +101,215 @@ fn breed_workes() {
 	});
 }
 
+#[test]
+fn breed_records_lineage() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		Balances::force_set_balance(RuntimeOrigin::root(), account_id, 10000000);
+
+		// 0 and 1 are founders, 2 and 3 their offspring, and 4 is bred from 2 and 3.
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(account_id), 0, 1, *b"abcdefgh"));
+		assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(account_id), 1, 0, *b"abcdefgh"));
+		assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(account_id), 2, 3, *b"abcdefgh"));
+
+		assert_eq!(KittiesModule::children(0), vec![2, 3]);
+		assert_eq!(KittiesModule::children(3), vec![4]);
+		assert_eq!(KittiesModule::children(4), Vec::<KittyId>::new());
+
+		let member = |kitty_id, generation, parents| FamilyMember { kitty_id, generation, parents };
+		assert_eq!(
+			KittiesModule::ancestry(4, 8),
+			vec![
+				member(4, 0, Some((2, 3))),
+				member(2, 1, Some((0, 1))),
+				member(3, 1, Some((1, 0))),
+				member(0, 2, None),
+				member(1, 2, None),
+			]
+		);
+		assert_eq!(
+			KittiesModule::ancestry(4, 1),
+			vec![member(4, 0, Some((2, 3))), member(2, 1, Some((0, 1))), member(3, 1, Some((1, 0)))]
+		);
+		assert_eq!(
+			KittiesModule::descendants(0, 8),
+			vec![
+				member(0, 0, None),
+				member(2, 1, Some((0, 1))),
+				member(3, 1, Some((1, 0))),
+				member(4, 2, Some((2, 3))),
+			]
+		);
+		assert_eq!(KittiesModule::ancestry(5, 8), vec![]);
+	});
+}
+
+#[test]
+fn inbreeding_coefficient_works() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		Balances::force_set_balance(RuntimeOrigin::root(), account_id, 10000000);
+
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(account_id), 0, 1, *b"abcdefgh"));
+		assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(account_id), 0, 1, *b"abcdefgh"));
+		// Full siblings.
+		assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(account_id), 2, 3, *b"abcdefgh"));
+
+		assert_eq!(KittiesModule::inbreeding_coefficient(0), Perbill::zero());
+		assert_eq!(KittiesModule::inbreeding_coefficient(2), Perbill::zero());
+		assert_eq!(KittiesModule::inbreeding_coefficient(4), Perbill::from_percent(25));
+
+		// Parent and offspring.
+		assert_eq!(KittiesModule::offspring_inbreeding_coefficient(0, 2), Perbill::from_percent(25));
+		// An inbred kitty and its parent.
+		assert_eq!(
+			KittiesModule::offspring_inbreeding_coefficient(4, 2),
+			Perbill::from_rational(3u32, 8u32)
+		);
+		assert_eq!(KittiesModule::offspring_inbreeding_coefficient(0, 1), Perbill::zero());
+	});
+}
+
+#[test]
+fn migration_fills_kitty_children() {
+	new_test_ext().execute_with(|| {
+		crate::KittyParents::<Test>::insert(2, (0, 1));
+		crate::KittyParents::<Test>::insert(3, (2, 1));
+		StorageVersion::new(2).put::<KittiesModule>();
+
+		KittiesModule::on_runtime_upgrade();
+
+		assert_eq!(KittiesModule::children(0), vec![2]);
+		assert_eq!(KittiesModule::children(1), vec![2, 3]);
+		assert_eq!(KittiesModule::children(2), vec![3]);
+		assert_eq!(KittiesModule::on_chain_storage_version(), 4);
+	});
+}
+
+#[test]
+fn migration_refuses_storage_version_1() {
+	new_test_ext().execute_with(|| {
+		// A kitty with a four byte name, as stored before names were widened.
+		let key = Kitties::<Test>::hashed_key_for(0);
+		unhashed::put_raw(&key, &([0x0f_u8; 16], *b"abcd").encode());
+		StorageVersion::new(1).put::<KittiesModule>();
+
+		KittiesModule::on_runtime_upgrade();
+
+		assert_eq!(unhashed::get_raw(&key), Some(([0x0f_u8; 16], *b"abcd").encode()));
+		assert_eq!(KittiesModule::on_chain_storage_version(), 1);
+		#[cfg(feature = "try-runtime")]
+		assert!(KittiesModule::pre_upgrade().is_err());
+	});
+}
+
+#[test]
+fn rarity_score_favours_uncommon_genes_and_later_generations() {
+	// Four set bits is the most common, none at all the least.
+	assert_eq!(rarity_score(&[0x0f; 16], 0), 16 * 3);
+	assert_eq!(rarity_score(&[0x00; 16], 0), 16 * 256);
+	assert_eq!(rarity_score(&[0x0f; 16], 2), 16 * 3 + 2 * GENERATION_RARITY);
+}
+
+#[test]
+fn kitties_get_generation_and_rarity_at_mint() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		Balances::force_set_balance(RuntimeOrigin::root(), account_id, 10000000);
+
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(account_id), 0, 1, *b"abcdefgh"));
+		assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(account_id), 2, 0, *b"abcdefgh"));
+
+		for (kitty_id, generation) in [(0, 0), (1, 0), (2, 1), (3, 2)] {
+			let kitty = KittiesModule::kitties(kitty_id).unwrap();
+			assert_eq!(kitty.generation, generation);
+			assert_eq!(kitty.rarity, rarity_score(&kitty.dna, generation));
+		}
+	});
+}
+
+#[test]
+fn leaderboard_keeps_the_rarest_kitties() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		Balances::force_set_balance(RuntimeOrigin::root(), account_id, 10000000);
+
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		let rarity = |kitty_id| KittiesModule::kitties(kitty_id).unwrap().rarity;
+		let mut expected = vec![(0, rarity(0)), (1, rarity(1))];
+		expected.sort_by(|(_, a), (_, b)| b.cmp(a));
+		assert_eq!(KittiesModule::leaderboard().into_inner(), expected);
+
+		assert_ok!(KittiesModule::force_burn(RuntimeOrigin::root(), 0));
+		assert_eq!(KittiesModule::leaderboard().into_inner(), vec![(1, rarity(1))]);
+
+		// The board holds three kitties; ties go to the kitty that entered first.
+		Leaderboard::<Test>::kill();
+		for (kitty_id, rarity) in [(10, 10), (11, 30), (12, 20), (13, 20), (14, 40), (15, 5)] {
+			KittiesModule::enter_leaderboard(kitty_id, rarity);
+		}
+		assert_eq!(KittiesModule::leaderboard().into_inner(), vec![(14, 40), (11, 30), (12, 20)]);
+	});
+}
+
+#[test]
+fn burning_a_leaderboard_kitty_leaves_a_gap() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		Balances::force_set_balance(RuntimeOrigin::root(), account_id, 10000000);
+
+		for _ in 0..4 {
+			assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		}
+		let board = KittiesModule::leaderboard().into_inner();
+		assert_eq!(board.len(), 3);
+		let left_off = (0..4).find(|kitty_id| board.iter().all(|(id, _)| id != kitty_id)).unwrap();
+
+		// The kitty left off the board does not take the burned kitty's place.
+		let (burned, _) = board[0];
+		assert_ok!(KittiesModule::force_burn(RuntimeOrigin::root(), burned));
+		assert_eq!(KittiesModule::leaderboard().into_inner(), board[1..].to_vec());
+		assert!(KittiesModule::kitties(left_off).is_some());
+	});
+}
+
+#[test]
+fn migration_sets_generation_and_rarity() {
+	new_test_ext().execute_with(|| {
+		let old_kitty = |byte| OldKitty { dna: [byte; 16], name: *b"abcdefgh" };
+		for (kitty_id, kitty) in [(0, old_kitty(0x0f)), (1, old_kitty(0x00)), (3, old_kitty(0x0f))] {
+			unhashed::put(&Kitties::<Test>::hashed_key_for(kitty_id), &kitty);
+		}
+		// Kitty 2 was burned, and 3 was bred from it.
+		crate::KittyParents::<Test>::insert(2, (0, 1));
+		crate::KittyParents::<Test>::insert(3, (2, 0));
+		crate::NextKittyId::<Test>::put(4);
+		StorageVersion::new(3).put::<KittiesModule>();
+
+		KittiesModule::on_runtime_upgrade();
+
+		let kitty = KittiesModule::kitties(3).unwrap();
+		assert_eq!((kitty.dna, kitty.name), ([0x0f; 16], *b"abcdefgh"));
+		assert_eq!(kitty.generation, 2);
+		assert_eq!(kitty.rarity, rarity_score(&[0x0f; 16], 2));
+		assert_eq!(
+			KittiesModule::leaderboard().into_inner(),
+			vec![
+				(1, rarity_score(&[0x00; 16], 0)),
+				(3, rarity_score(&[0x0f; 16], 2)),
+				(0, rarity_score(&[0x0f; 16], 0)),
+			]
+		);
+		assert_eq!(KittiesModule::on_chain_storage_version(), 4);
+	});
+}
 
 #[test]
 fn set_price_and_buy_works() {
@@ -111,5 +336,405 @@ fn set_price_and_buy_works() {
 	});
 }
 
+#[test]
+fn force_transfer_and_force_burn_work() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = 0;
+		let account_id = 1;
+		let another_account_id = 2;
+
+		Balances::force_set_balance(RuntimeOrigin::root(), account_id, 10000000);
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		assert_ok!(KittiesModule::set_price(RuntimeOrigin::signed(account_id), kitty_id, 100));
+
+		assert_noop!(
+			KittiesModule::force_transfer(RuntimeOrigin::signed(another_account_id), another_account_id, kitty_id),
+			sp_runtime::traits::BadOrigin
+		);
+		assert_ok!(KittiesModule::force_transfer(RuntimeOrigin::root(), another_account_id, kitty_id));
+		assert_eq!(KittiesModule::kitty_owner(kitty_id), Some(another_account_id));
+		assert_eq!(KittiesModule::kitty_prices(kitty_id), None);
+
+		assert_ok!(KittiesModule::force_burn(RuntimeOrigin::root(), kitty_id));
+		System::assert_last_event(Event::KittyBurned{ owner: another_account_id, kitty_id }.into());
+		assert_eq!(KittiesModule::kitties(kitty_id), None);
+		assert_eq!(KittiesModule::kitty_owner(kitty_id), None);
+		assert_noop!(KittiesModule::force_burn(RuntimeOrigin::root(), kitty_id), Error::<Test>::InvalidKittyId);
+	});
+}
+
+#[test]
+fn set_mint_price_overrides_kitty_price() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+
+		Balances::force_set_balance(RuntimeOrigin::root(), account_id, 10000000);
+		assert_eq!(KittiesModule::current_mint_price(), KittyPrice::get());
+
+		assert_ok!(KittiesModule::set_mint_price(RuntimeOrigin::root(), Some(1000)));
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		assert_eq!(Balances::free_balance(account_id), 10000000 - 1000);
+
+		assert_ok!(KittiesModule::set_mint_price(RuntimeOrigin::root(), None));
+		assert_eq!(KittiesModule::current_mint_price(), KittyPrice::get());
+	});
+}
+
+#[test]
+fn pause_minting_blocks_create_and_breed() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+
+		Balances::force_set_balance(RuntimeOrigin::root(), account_id, 10000000);
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+
+		assert_noop!(KittiesModule::pause_minting(RuntimeOrigin::signed(account_id), true), sp_runtime::traits::BadOrigin);
+		assert_ok!(KittiesModule::pause_minting(RuntimeOrigin::root(), true));
+		assert_noop!(
+			KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"),
+			Error::<Test>::MintingPaused
+		);
+		assert_noop!(
+			KittiesModule::breed(RuntimeOrigin::signed(account_id), 0, 1, *b"abcdefgh"),
+			Error::<Test>::MintingPaused
+		);
+
+		// Existing kitties can still be traded.
+		assert_ok!(KittiesModule::transfer(RuntimeOrigin::signed(account_id), 2, 0));
+
+		assert_ok!(KittiesModule::pause_minting(RuntimeOrigin::root(), false));
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+	});
+}
+
+#[test]
+fn pausing_trading_keeps_create_and_transfer_working() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = 0;
+		let account_id = 1;
+		let another_account_id = 2;
+
+		Balances::force_set_balance(RuntimeOrigin::root(), account_id, 10000000);
+		Balances::force_set_balance(RuntimeOrigin::root(), another_account_id, 10000000);
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		assert_ok!(KittiesModule::set_price(RuntimeOrigin::signed(account_id), kitty_id, 100));
+
+		assert_noop!(
+			KittiesModule::set_call_group_paused(RuntimeOrigin::signed(account_id), CallGroup::Trading, true),
+			sp_runtime::traits::BadOrigin
+		);
+		assert_ok!(KittiesModule::set_call_group_paused(RuntimeOrigin::root(), CallGroup::Trading, true));
+		System::assert_last_event(Event::CallGroupPauseSet{ group: CallGroup::Trading, paused: true }.into());
+
+		assert_noop!(KittiesModule::buy(RuntimeOrigin::signed(another_account_id), kitty_id), Error::<Test>::TradingPaused);
+		assert_noop!(KittiesModule::set_price(RuntimeOrigin::signed(account_id), kitty_id, 100), Error::<Test>::TradingPaused);
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		assert_ok!(KittiesModule::transfer(RuntimeOrigin::signed(account_id), another_account_id, 1));
+
+		assert_ok!(KittiesModule::set_call_group_paused(RuntimeOrigin::root(), CallGroup::Trading, false));
+		assert!(!KittiesModule::is_paused(CallGroup::Trading));
+		assert_ok!(KittiesModule::buy(RuntimeOrigin::signed(another_account_id), kitty_id));
+	});
+}
+
+#[test]
+fn pausing_transfers_blocks_transfer() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+
+		Balances::force_set_balance(RuntimeOrigin::root(), account_id, 10000000);
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+
+		assert_ok!(KittiesModule::set_call_group_paused(RuntimeOrigin::root(), CallGroup::Transfers, true));
+		assert_noop!(KittiesModule::transfer(RuntimeOrigin::signed(account_id), 2, 0), Error::<Test>::TransfersPaused);
+
+		// The privileged origin can still move kitties.
+		assert_ok!(KittiesModule::force_transfer(RuntimeOrigin::root(), 2, 0));
+	});
+}
+
+fn fund_pallet_account() {
+	let pallet_account: u64 = KittyPalletId::get().into_account_truncating();
+	Balances::force_set_balance(RuntimeOrigin::root(), pallet_account, 10000000).unwrap();
+}
+
+#[test]
+fn staked_kitties_are_locked() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		Balances::force_set_balance(RuntimeOrigin::root(), account_id, 10000000);
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
 
+		assert_noop!(KittiesModule::stake(RuntimeOrigin::signed(2), 0), Error::<Test>::NotOwner);
+		assert_ok!(KittiesModule::stake(RuntimeOrigin::signed(account_id), 0));
+		System::assert_last_event(Event::KittyStaked { who: account_id, kitty_id: 0 }.into());
+		assert_noop!(KittiesModule::stake(RuntimeOrigin::signed(account_id), 0), Error::<Test>::KittyStaked);
 
+		assert_noop!(KittiesModule::transfer(RuntimeOrigin::signed(account_id), 2, 0), Error::<Test>::KittyStaked);
+		assert_noop!(KittiesModule::set_price(RuntimeOrigin::signed(account_id), 0, 1000), Error::<Test>::KittyStaked);
+		assert_noop!(
+			KittiesModule::breed(RuntimeOrigin::signed(account_id), 1, 0, *b"abcdefgh"),
+			Error::<Test>::KittyStaked
+		);
+
+		// A kitty on sale cannot be staked.
+		assert_ok!(KittiesModule::set_price(RuntimeOrigin::signed(account_id), 1, 1000));
+		assert_noop!(KittiesModule::stake(RuntimeOrigin::signed(account_id), 1), Error::<Test>::AlreadyOnSale);
+
+		// The privileged origin cancels the stake.
+		assert_ok!(KittiesModule::force_transfer(RuntimeOrigin::root(), 2, 0));
+		assert!(!KittiesModule::is_staked(0));
+	});
+}
+
+#[test]
+fn staking_rewards_are_paid_until_release() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		Balances::force_set_balance(RuntimeOrigin::root(), account_id, 10000000);
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		fund_pallet_account();
+		let rarity = KittiesModule::kitties(0).unwrap().rarity as u128;
+
+		assert_ok!(KittiesModule::stake(RuntimeOrigin::signed(account_id), 0));
+		System::set_block_number(5);
+		assert_eq!(KittiesModule::pending_rewards(0), 4 * rarity);
+
+		let balance = Balances::free_balance(account_id);
+		assert_ok!(KittiesModule::claim_rewards(RuntimeOrigin::signed(account_id), 0));
+		System::assert_last_event(Event::RewardsClaimed { who: account_id, kitty_id: 0, amount: 4 * rarity }.into());
+		assert_eq!(Balances::free_balance(account_id), balance + 4 * rarity);
+		assert_eq!(KittiesModule::pending_rewards(0), 0);
+
+		// Rewards stop at `unstake`, and the kitty stays locked while unbonding.
+		System::set_block_number(7);
+		assert_ok!(KittiesModule::unstake(RuntimeOrigin::signed(account_id), 0));
+		System::assert_last_event(Event::KittyUnstaked { who: account_id, kitty_id: 0, unbonding_until: 17 }.into());
+		assert_noop!(KittiesModule::unstake(RuntimeOrigin::signed(account_id), 0), Error::<Test>::AlreadyUnstaked);
+		System::set_block_number(16);
+		assert_eq!(KittiesModule::pending_rewards(0), 2 * rarity);
+		assert_ok!(KittiesModule::claim_rewards(RuntimeOrigin::signed(account_id), 0));
+		assert_eq!(Balances::free_balance(account_id), balance + 6 * rarity);
+		assert_eq!(KittiesModule::pending_rewards(0), 0);
+		assert!(KittiesModule::is_staked(0));
+		assert_noop!(KittiesModule::transfer(RuntimeOrigin::signed(account_id), 2, 0), Error::<Test>::KittyStaked);
+
+		System::set_block_number(17);
+		assert_ok!(KittiesModule::claim_rewards(RuntimeOrigin::signed(account_id), 0));
+		System::assert_last_event(Event::KittyReleased { who: account_id, kitty_id: 0 }.into());
+		assert_eq!(Balances::free_balance(account_id), balance + 6 * rarity);
+		assert_ok!(KittiesModule::transfer(RuntimeOrigin::signed(account_id), 2, 0));
+		assert_noop!(KittiesModule::claim_rewards(RuntimeOrigin::signed(2), 0), Error::<Test>::NotStaked);
+	});
+}
+
+#[test]
+fn unbonded_kitties_are_released_by_an_underfunded_pallet_account() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		Balances::force_set_balance(RuntimeOrigin::root(), account_id, 10000000);
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		let pallet_account: u64 = KittyPalletId::get().into_account_truncating();
+		assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), pallet_account, 0));
+		let rarity = KittiesModule::kitties(0).unwrap().rarity as u128;
+
+		// A staked kitty keeps its rewards owed until the pallet account can pay them.
+		assert_ok!(KittiesModule::stake(RuntimeOrigin::signed(account_id), 0));
+		System::set_block_number(5);
+		assert!(KittiesModule::claim_rewards(RuntimeOrigin::signed(account_id), 0).is_err());
+		assert_eq!(KittiesModule::pending_rewards(0), 4 * rarity);
+
+		// Once unbonded, it is released with what the account can spare above the existential deposit.
+		assert_ok!(KittiesModule::unstake(RuntimeOrigin::signed(account_id), 0));
+		System::set_block_number(15);
+		assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), pallet_account, EXISTENTIAL_DEPOSIT + rarity));
+		let balance = Balances::free_balance(account_id);
+		assert_ok!(KittiesModule::claim_rewards(RuntimeOrigin::signed(account_id), 0));
+		System::assert_has_event(Event::RewardsClaimed { who: account_id, kitty_id: 0, amount: rarity }.into());
+		System::assert_last_event(Event::KittyReleased { who: account_id, kitty_id: 0 }.into());
+		assert_eq!(Balances::free_balance(account_id), balance + rarity);
+		assert_eq!(Balances::free_balance(pallet_account), EXISTENTIAL_DEPOSIT);
+		assert!(!KittiesModule::is_staked(0));
+
+		// With nothing to spare, the kitty is still released.
+		assert_ok!(KittiesModule::stake(RuntimeOrigin::signed(account_id), 0));
+		System::set_block_number(20);
+		assert_ok!(KittiesModule::unstake(RuntimeOrigin::signed(account_id), 0));
+		System::set_block_number(30);
+		assert_ok!(KittiesModule::claim_rewards(RuntimeOrigin::signed(account_id), 0));
+		System::assert_last_event(Event::KittyReleased { who: account_id, kitty_id: 0 }.into());
+		assert_eq!(Balances::free_balance(account_id), balance + rarity);
+		assert!(!KittiesModule::is_staked(0));
+	});
+}
+
+#[test]
+fn pausing_staking_blocks_staking_calls() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		Balances::force_set_balance(RuntimeOrigin::root(), account_id, 10000000);
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(account_id), *b"abcdefgh"));
+		assert_ok!(KittiesModule::stake(RuntimeOrigin::signed(account_id), 0));
+
+		assert_ok!(KittiesModule::set_call_group_paused(RuntimeOrigin::root(), CallGroup::Staking, true));
+		assert_noop!(KittiesModule::unstake(RuntimeOrigin::signed(account_id), 0), Error::<Test>::StakingPaused);
+		assert_noop!(KittiesModule::claim_rewards(RuntimeOrigin::signed(account_id), 0), Error::<Test>::StakingPaused);
+
+		// Burning a staked kitty drops its stake.
+		assert_ok!(KittiesModule::force_burn(RuntimeOrigin::root(), 0));
+		assert!(!KittiesModule::is_staked(0));
+	});
+}
+
+#[test]
+fn genesis_kitties_are_created() {
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	crate::GenesisConfig::<Test> {
+		kitties: vec![(1, *b"abcdefgh", None), (2, *b"ijklmnop", Some(1000))],
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+
+	sp_io::TestExternalities::from(storage).execute_with(|| {
+		assert_eq!(KittiesModule::next_kitty_id(), 2);
+		assert_eq!(KittiesModule::kitty_owner(0), Some(1));
+		assert_eq!(KittiesModule::kitty_owner(1), Some(2));
+		assert_eq!(KittiesModule::kitties(1).unwrap().name, *b"ijklmnop");
+		assert_ne!(KittiesModule::kitties(0).unwrap().dna, KittiesModule::kitties(1).unwrap().dna);
+		assert_eq!(KittiesModule::kitty_prices(0), None);
+		assert_eq!(KittiesModule::kitty_prices(1), Some(1000));
+
+		System::set_block_number(1);
+		Balances::force_set_balance(RuntimeOrigin::root(), 1, 10000000);
+		assert_ok!(KittiesModule::buy(RuntimeOrigin::signed(1), 1));
+		assert_eq!(KittiesModule::kitty_owner(1), Some(1));
+	});
+}
+
+fn indexed_kitties(who: u64) -> Option<Vec<KittyId>> {
+	StorageValueRef::persistent(&owned_kitties_key(&who)).get().unwrap()
+}
+
+#[test]
+fn offchain_worker_indexes_owners_and_sales() {
+	let mut ext = new_test_ext();
+	let (offchain, _state) = TestOffchainExt::new();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+
+	ext.execute_with(|| {
+		Balances::force_set_balance(RuntimeOrigin::root(), 1, 10000000);
+		Balances::force_set_balance(RuntimeOrigin::root(), 2, 10000000);
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1), *b"abcdefgh"));
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1), *b"ijklmnop"));
+		assert_ok!(KittiesModule::set_price(RuntimeOrigin::signed(1), 0, 1000));
+		assert_ok!(KittiesModule::buy(RuntimeOrigin::signed(2), 0));
+
+		KittiesModule::offchain_worker(1);
+		assert_eq!(indexed_kitties(1), Some(vec![1]));
+		assert_eq!(indexed_kitties(2), Some(vec![0]));
+
+		System::reset_events();
+		System::set_block_number(3);
+		assert_ok!(KittiesModule::transfer(RuntimeOrigin::signed(2), 3, 0));
+		assert_ok!(KittiesModule::force_burn(RuntimeOrigin::root(), 1));
+		KittiesModule::offchain_worker(3);
+		assert_eq!(indexed_kitties(1), None);
+		assert_eq!(indexed_kitties(2), None);
+		assert_eq!(indexed_kitties(3), Some(vec![0]));
+
+		// A worker running late for an earlier block does not undo the later transfer.
+		System::reset_events();
+		System::set_block_number(2);
+		assert_ok!(KittiesModule::transfer(RuntimeOrigin::signed(3), 2, 0));
+		KittiesModule::offchain_worker(2);
+		assert_eq!(indexed_kitties(2), None);
+		assert_eq!(indexed_kitties(3), Some(vec![0]));
+	});
+}
+
+#[test]
+fn owner_index_misses_blocks_the_worker_skipped() {
+	let mut ext = new_test_ext();
+	let (offchain, _state) = TestOffchainExt::new();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+
+	ext.execute_with(|| {
+		Balances::force_set_balance(RuntimeOrigin::root(), 1, 10000000);
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1), *b"abcdefgh"));
+		KittiesModule::offchain_worker(1);
+		assert_eq!(indexed_kitties(1), Some(vec![0]));
+
+		// No worker runs for block 2, as during a major sync.
+		System::reset_events();
+		System::set_block_number(2);
+		assert_ok!(KittiesModule::transfer(RuntimeOrigin::signed(1), 2, 0));
+
+		System::reset_events();
+		System::set_block_number(3);
+		KittiesModule::offchain_worker(3);
+		assert_eq!(KittiesModule::kitty_owner(0), Some(2));
+		assert_eq!(indexed_kitties(1), Some(vec![0]));
+		assert_eq!(indexed_kitties(2), None);
+	});
+}
+
+#[test]
+fn buy_records_sales_in_offchain_db() {
+	let mut ext = new_test_ext();
+	let (first, second) = ext.execute_with(|| {
+		Balances::force_set_balance(RuntimeOrigin::root(), 1, 10000000);
+		Balances::force_set_balance(RuntimeOrigin::root(), 2, 10000000);
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1), *b"abcdefgh"));
+		assert_ok!(KittiesModule::set_price(RuntimeOrigin::signed(1), 0, 1000));
+		assert_ok!(KittiesModule::buy(RuntimeOrigin::signed(2), 0));
+		let first = KittiesModule::last_sale().unwrap();
+
+		System::set_block_number(2);
+		assert_ok!(KittiesModule::set_price(RuntimeOrigin::signed(2), 0, 2000));
+		assert_ok!(KittiesModule::buy(RuntimeOrigin::signed(1), 0));
+		let second = KittiesModule::last_sale().unwrap();
+		assert_eq!(KittiesModule::last_kitty_sale(0), Some(second));
+		assert_eq!(KittiesModule::last_account_sale(1), Some(second));
+		assert_eq!(KittiesModule::last_account_sale(2), Some(second));
+		(first, second)
+	});
+
+	ext.persist_offchain_overlay();
+	let db = ext.offchain_db();
+	let sale = |digest: H256| {
+		let record = db.get(&sale_key(&digest)).unwrap();
+		SaleRecord::<u64, Balance, u64, H256>::decode(&mut &record[..]).unwrap()
+	};
+	assert_eq!(
+		sale(first),
+		SaleRecord {
+			kitty_id: 0,
+			seller: 1,
+			buyer: 2,
+			price: 1000,
+			block_number: 1,
+			previous: None,
+			previous_of_kitty: None,
+			previous_of_seller: None,
+			previous_of_buyer: None,
+		}
+	);
+	assert_eq!(
+		sale(second),
+		SaleRecord {
+			kitty_id: 0,
+			seller: 2,
+			buyer: 1,
+			price: 2000,
+			block_number: 2,
+			previous: Some(first),
+			previous_of_kitty: Some(first),
+			previous_of_seller: Some(first),
+			previous_of_buyer: Some(first),
+		}
+	);
+}
@@ -28,7 +28,8 @@ pub use frame_support::{
 	PalletId,
 	construct_runtime, parameter_types,
 	traits::{
-		ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo,
+		ConstU128, ConstU32, ConstU64, ConstU8, Contains, KeyOwnerProofSystem, Randomness,
+		StorageInfo,
 	},
 	weights::{
 		constants::{
@@ -40,6 +41,7 @@ pub use frame_support::{
 };
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
+pub use pallet_kitties::Call as KittiesCall;
 pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
 #[cfg(any(feature = "std", test))]
@@ -107,10 +109,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 300,
+	spec_version: 303,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -157,7 +159,7 @@ parameter_types! {
 
 impl frame_system::Config for Runtime {
 	/// The basic call filter to use in dispatchable.
-	type BaseCallFilter = frame_support::traits::Everything;
+	type BaseCallFilter = TxPause;
 	/// Block & extrinsics weights: base values and limits.
 	type BlockWeights = BlockWeights;
 	/// The maximum length of a block (in bytes).
@@ -282,6 +284,9 @@ impl pallet_insecure_randomness_collective_flip::Config for Runtime {}
 parameter_types! {
 	pub const KittyPalletId: PalletId = PalletId(*b"py/kitty");
 	pub const KittyPrice: Balance = EXISTENTIAL_DEPOSIT * 100;
+	pub const KittyLeaderboardSize: u32 = 100;
+	pub const KittyStakingReward: Balance = 1;
+	pub const KittyUnbondingPeriod: BlockNumber = DAYS;
 }
 
 impl pallet_kitties::Config for Runtime {
@@ -290,6 +295,26 @@ impl pallet_kitties::Config for Runtime {
 	type Currency = Balances;
 	type KittyPrice = KittyPrice;
 	type PalletId = KittyPalletId; 
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type LeaderboardSize = KittyLeaderboardSize;
+	type StakingReward = KittyStakingReward;
+	type UnbondingPeriod = KittyUnbondingPeriod;
+}
+
+/// Calls `TxPause` can never pause: those of `Sudo`, so root can always recover, and the
+/// `Timestamp` inherent, without which no block can be authored.
+pub struct UnpausableCalls;
+impl Contains<pallet_tx_pause::RuntimeCallNameOf<Runtime>> for UnpausableCalls {
+	fn contains(full_name: &pallet_tx_pause::RuntimeCallNameOf<Runtime>) -> bool {
+		matches!(full_name.0.as_slice(), b"Sudo" | b"Timestamp")
+	}
+}
+
+impl pallet_tx_pause::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type WhitelistedCalls = UnpausableCalls;
+	type MaxNameLen = ConstU32<256>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -311,6 +336,7 @@ construct_runtime!(
 		TemplateModule: pallet_template,
 		KittiesModule: pallet_kitties,
 		MyRandomness: pallet_insecure_randomness_collective_flip::{Pallet, Storage},
+		TxPause: pallet_tx_pause,
 	}
 );
 
@@ -529,6 +555,37 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_runtime_api::KittiesApi<Block> for Runtime {
+		fn ancestry(
+			kitty_id: pallet_kitties::KittyId,
+			depth: u32,
+		) -> Vec<pallet_kitties_runtime_api::FamilyMember> {
+			KittiesModule::ancestry(kitty_id, depth)
+		}
+
+		fn descendants(
+			kitty_id: pallet_kitties::KittyId,
+			depth: u32,
+		) -> Vec<pallet_kitties_runtime_api::FamilyMember> {
+			KittiesModule::descendants(kitty_id, depth)
+		}
+
+		fn inbreeding_coefficient(kitty_id: pallet_kitties::KittyId) -> Perbill {
+			KittiesModule::inbreeding_coefficient(kitty_id)
+		}
+
+		fn offspring_inbreeding_coefficient(
+			parent_1: pallet_kitties::KittyId,
+			parent_2: pallet_kitties::KittyId,
+		) -> Perbill {
+			KittiesModule::offspring_inbreeding_coefficient(parent_1, parent_2)
+		}
+
+		fn leaderboard() -> Vec<(pallet_kitties::KittyId, pallet_kitties::Rarity)> {
+			KittiesModule::leaderboard().into_inner()
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -596,7 +653,7 @@ impl_runtime_apis! {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use frame_support::traits::WhitelistedStorageKeys;
+	use frame_support::{assert_noop, assert_ok, traits::WhitelistedStorageKeys};
 	use sp_core::hexdisplay::HexDisplay;
 	use std::collections::HashSet;
 
@@ -628,4 +685,50 @@ mod tests {
 			whitelist.contains("26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7")
 		);
 	}
+
+	fn full_name(
+		pallet_name: &[u8],
+		call_name: &[u8],
+	) -> pallet_tx_pause::RuntimeCallNameOf<Runtime> {
+		(pallet_name.to_vec().try_into().unwrap(), call_name.to_vec().try_into().unwrap())
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap()
+			.into()
+	}
+
+	#[test]
+	fn sudo_and_tx_pause_cannot_be_paused() {
+		new_test_ext().execute_with(|| {
+			for name in [
+				full_name(b"Sudo", b"sudo"),
+				full_name(b"Sudo", b"set_key"),
+				full_name(b"TxPause", b"pause"),
+				full_name(b"TxPause", b"unpause"),
+				full_name(b"Timestamp", b"set"),
+			] {
+				assert_noop!(
+					TxPause::pause(RuntimeOrigin::root(), name),
+					pallet_tx_pause::Error::<Runtime>::Unpausable
+				);
+			}
+		});
+	}
+
+	#[test]
+	fn paused_calls_are_filtered() {
+		new_test_ext().execute_with(|| {
+			let call = RuntimeCall::KittiesModule(pallet_kitties::Call::buy { kitty_id: 0 });
+			assert!(<Runtime as frame_system::Config>::BaseCallFilter::contains(&call));
+
+			assert_ok!(TxPause::pause(RuntimeOrigin::root(), full_name(b"KittiesModule", b"buy")));
+			assert!(!<Runtime as frame_system::Config>::BaseCallFilter::contains(&call));
+
+			let sudo = RuntimeCall::Sudo(pallet_sudo::Call::sudo { call: Box::new(call.clone()) });
+			assert!(<Runtime as frame_system::Config>::BaseCallFilter::contains(&sudo));
+		});
+	}
 }
@@ -0,0 +1,134 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Pause individual calls of any pallet in the runtime.
+//!
+//! Paused calls are identified by their `(pallet_name, call_name)` pair as reported by
+//! [`GetCallMetadata`]. The pallet implements [`Contains`] for the runtime call type and is meant
+//! to be used as `frame_system::Config::BaseCallFilter`, so paused calls are rejected for every
+//! origin except root.
+//!
+//! Calls of this pallet and calls accepted by `Config::WhitelistedCalls` can never be paused.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{CallMetadata, Contains, GetCallMetadata, PalletInfoAccess},
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_std::prelude::*;
+
+	/// The name of a pallet, as configured in `construct_runtime!`.
+	pub type PalletNameOf<T> = BoundedVec<u8, <T as Config>::MaxNameLen>;
+	/// The name of a call of a pallet.
+	pub type CallNameOf<T> = BoundedVec<u8, <T as Config>::MaxNameLen>;
+	/// The full name of a call: its pallet name and its call name.
+	pub type RuntimeCallNameOf<T> = (PalletNameOf<T>, CallNameOf<T>);
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// The overarching call type, whose calls can be paused.
+		type RuntimeCall: Parameter + GetCallMetadata;
+		/// Calls that can never be paused, e.g. those of `Sudo`.
+		type WhitelistedCalls: Contains<RuntimeCallNameOf<Self>>;
+		/// The maximum length of a pallet or call name.
+		#[pallet::constant]
+		type MaxNameLen: Get<u32>;
+	}
+
+	/// The calls that are currently paused.
+	#[pallet::storage]
+	pub type PausedCalls<T: Config> =
+		StorageMap<_, Blake2_128Concat, RuntimeCallNameOf<T>, (), OptionQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A call was paused.
+		CallPaused { full_name: RuntimeCallNameOf<T> },
+		/// A call was unpaused.
+		CallUnpaused { full_name: RuntimeCallNameOf<T> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The call is already paused.
+		IsPaused,
+		/// The call is not paused.
+		IsUnpaused,
+		/// The call belongs to this pallet or is whitelisted and cannot be paused.
+		Unpausable,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Pause the call with the given full name. The origin must be root.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn pause(origin: OriginFor<T>, full_name: RuntimeCallNameOf<T>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			ensure!(Self::can_pause(&full_name), Error::<T>::Unpausable);
+			ensure!(!PausedCalls::<T>::contains_key(&full_name), Error::<T>::IsPaused);
+			PausedCalls::<T>::insert(&full_name, ());
+
+			Self::deposit_event(Event::CallPaused { full_name });
+			Ok(())
+		}
+
+		/// Unpause the call with the given full name. The origin must be root.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn unpause(origin: OriginFor<T>, full_name: RuntimeCallNameOf<T>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			ensure!(PausedCalls::<T>::take(&full_name).is_some(), Error::<T>::IsUnpaused);
+
+			Self::deposit_event(Event::CallUnpaused { full_name });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether the call with the given full name may be paused.
+		pub fn can_pause(full_name: &RuntimeCallNameOf<T>) -> bool {
+			full_name.0.as_slice() != <Self as PalletInfoAccess>::name().as_bytes() &&
+				!T::WhitelistedCalls::contains(full_name)
+		}
+
+		/// Whether the call with the given pallet and call name is paused.
+		///
+		/// Names longer than `MaxNameLen` cannot be paused.
+		pub fn is_paused(pallet_name: &str, call_name: &str) -> bool {
+			let pallet_name = PalletNameOf::<T>::try_from(pallet_name.as_bytes().to_vec());
+			let call_name = CallNameOf::<T>::try_from(call_name.as_bytes().to_vec());
+			match (pallet_name, call_name) {
+				(Ok(pallet_name), Ok(call_name)) =>
+					PausedCalls::<T>::contains_key((pallet_name, call_name)),
+				_ => false,
+			}
+		}
+	}
+
+	impl<T: Config> Contains<<T as Config>::RuntimeCall> for Pallet<T> {
+		/// Accept every call that is not paused.
+		fn contains(call: &<T as Config>::RuntimeCall) -> bool {
+			let CallMetadata { pallet_name, function_name } = call.get_call_metadata();
+			!Self::is_paused(pallet_name, function_name)
+		}
+	}
+}
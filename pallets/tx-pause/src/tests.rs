@@ -0,0 +1,65 @@
+use crate::{mock::*, Error, Event, RuntimeCallNameOf};
+use frame_support::{assert_noop, assert_ok, dispatch::Dispatchable, traits::Contains};
+use sp_runtime::traits::BadOrigin;
+
+fn full_name(pallet_name: &[u8], call_name: &[u8]) -> RuntimeCallNameOf<Test> {
+	(pallet_name.to_vec().try_into().unwrap(), call_name.to_vec().try_into().unwrap())
+}
+
+fn remark() -> RuntimeCall {
+	RuntimeCall::System(frame_system::Call::remark { remark: vec![] })
+}
+
+#[test]
+fn pause_filters_the_call() {
+	new_test_ext().execute_with(|| {
+		assert!(TxPause::contains(&remark()));
+		assert_ok!(remark().dispatch(RuntimeOrigin::signed(1)));
+
+		let name = full_name(b"System", b"remark");
+		assert_noop!(TxPause::pause(RuntimeOrigin::signed(1), name.clone()), BadOrigin);
+		assert_ok!(TxPause::pause(RuntimeOrigin::root(), name.clone()));
+		System::assert_last_event(Event::CallPaused { full_name: name.clone() }.into());
+
+		assert!(!TxPause::contains(&remark()));
+		assert_noop!(
+			remark().dispatch(RuntimeOrigin::signed(1)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+		// Other calls of the same pallet are not affected.
+		assert!(TxPause::contains(&RuntimeCall::System(frame_system::Call::remark_with_event {
+			remark: vec![]
+		})));
+		assert_noop!(TxPause::pause(RuntimeOrigin::root(), name), Error::<Test>::IsPaused);
+	});
+}
+
+#[test]
+fn unpause_works() {
+	new_test_ext().execute_with(|| {
+		let name = full_name(b"System", b"remark");
+		assert_noop!(
+			TxPause::unpause(RuntimeOrigin::root(), name.clone()),
+			Error::<Test>::IsUnpaused
+		);
+
+		assert_ok!(TxPause::pause(RuntimeOrigin::root(), name.clone()));
+		assert_noop!(TxPause::unpause(RuntimeOrigin::signed(1), name.clone()), BadOrigin);
+		assert_ok!(TxPause::unpause(RuntimeOrigin::root(), name.clone()));
+		System::assert_last_event(Event::CallUnpaused { full_name: name }.into());
+		assert!(TxPause::contains(&remark()));
+	});
+}
+
+#[test]
+fn own_and_whitelisted_calls_cannot_be_paused() {
+	new_test_ext().execute_with(|| {
+		for name in [
+			full_name(b"TxPause", b"pause"),
+			full_name(b"TxPause", b"unpause"),
+			full_name(b"System", b"remark_with_event"),
+		] {
+			assert_noop!(TxPause::pause(RuntimeOrigin::root(), name), Error::<Test>::Unpausable);
+		}
+	});
+}
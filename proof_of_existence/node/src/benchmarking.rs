@@ -5,10 +5,10 @@
 use crate::service::FullClient;
 
 use node_template_runtime as runtime;
-use runtime::{AccountId, Balance, BalancesCall, SystemCall};
+use runtime::{AccountId, Balance, BalancesCall, PoeCall, SystemCall};
 use sc_cli::Result;
 use sc_client_api::BlockBackend;
-use sp_core::{Encode, Pair};
+use sp_core::{Encode, Get, Pair};
 use sp_inherents::{InherentData, InherentDataProvider};
 use sp_keyring::Sr25519Keyring;
 use sp_runtime::{OpaqueExtrinsic, SaturatedConversion};
@@ -95,6 +95,58 @@ impl frame_benchmarking_cli::ExtrinsicBuilder for TransferKeepAliveBuilder {
 	}
 }
 
+/// The accounts endowed in the development chain spec, which sign the `create_claim` extrinsics.
+const CLAIM_SIGNERS: [&str; 4] = ["//Alice", "//Bob", "//Alice//stash", "//Bob//stash"];
+
+/// Generates `PoeModule::create_claim` extrinsics for the benchmarks.
+///
+/// Every extrinsic claims its own nonce. An account can own at most `MaxClaimsPerOwner` claims,
+/// so the extrinsics are signed by each of [`CLAIM_SIGNERS`] in turn.
+///
+/// Note: Should only be used for benchmarking.
+pub struct CreateClaimBuilder {
+	client: Arc<FullClient>,
+}
+
+impl CreateClaimBuilder {
+	/// Creates a new [`Self`] from the given client.
+	pub fn new(client: Arc<FullClient>) -> Self {
+		Self { client }
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder for CreateClaimBuilder {
+	fn pallet(&self) -> &str {
+		"poe"
+	}
+
+	fn extrinsic(&self) -> &str {
+		"create_claim"
+	}
+
+	fn build(&self, nonce: u32) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		let signers = CLAIM_SIGNERS.len() as u32;
+		let max_claims = <runtime::Runtime as pallet_poe::Config>::MaxClaimsPerOwner::get();
+		if nonce >= signers.saturating_mul(max_claims) {
+			return Err("Not enough accounts to sign claims; lower `--max-ext-per-block`")
+		}
+
+		let seed = CLAIM_SIGNERS[(nonce % signers) as usize];
+		let acc = sp_core::sr25519::Pair::from_string(seed, None)
+			.map_err(|_| "Invalid claim signer seed")?;
+		let claim = nonce.encode().try_into().map_err(|_| "Claim exceeds `MaxClaimLength`")?;
+		let extrinsic: OpaqueExtrinsic = create_benchmark_extrinsic(
+			self.client.as_ref(),
+			acc,
+			PoeCall::create_claim { claim }.into(),
+			nonce / signers,
+		)
+		.into();
+
+		Ok(extrinsic)
+	}
+}
+
 /// Create a transaction using the given `call`.
 ///
 /// Note: Should only be used for benchmarking.
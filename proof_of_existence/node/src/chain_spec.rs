@@ -1,12 +1,13 @@
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, PoeModuleConfig,
+	Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
 use sp_core::{sr25519, Pair, Public};
 use sp_runtime::traits::{IdentifyAccount, Verify};
+use std::{fs::File, path::Path};
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -31,6 +32,40 @@ where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
+/// A claim pre-registered at genesis, as read from a claims JSON file.
+#[derive(serde::Deserialize)]
+struct GenesisClaim {
+	/// The claim bytes, hex encoded with a `0x` prefix.
+	claim: sp_core::Bytes,
+	/// The SS58 address of the claim owner.
+	owner: AccountId,
+}
+
+/// Load the genesis claims from a JSON file.
+///
+/// The file holds a list of `{ "claim": "0x..", "owner": "5..." }` objects.
+pub fn load_genesis_claims(path: &Path) -> Result<Vec<(Vec<u8>, AccountId)>, String> {
+	let file = File::open(path)
+		.map_err(|e| format!("Error opening claims file `{}`: {}", path.display(), e))?;
+	let claims: Vec<GenesisClaim> = serde_json::from_reader(file)
+		.map_err(|e| format!("Error parsing claims file `{}`: {}", path.display(), e))?;
+
+	let max_claim_length = node_template_runtime::MaxClaimLength::get() as usize;
+	claims
+		.into_iter()
+		.map(|GenesisClaim { claim, owner }| {
+			if claim.len() > max_claim_length {
+				return Err(format!(
+					"Claim {} is longer than the maximum of {} bytes",
+					sp_core::hexdisplay::HexDisplay::from(&claim.0),
+					max_claim_length,
+				))
+			}
+			Ok((claim.0, owner))
+		})
+		.collect()
+}
+
 /// Generate an Aura authority key.
 pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
 	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
@@ -59,6 +94,8 @@ pub fn development_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 				],
+				// Pre-registered claims
+				vec![],
 				true,
 			)
 		},
@@ -107,6 +144,51 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				// Pre-registered claims
+				vec![],
+				true,
+			)
+		},
+		// Bootnodes
+		vec![],
+		// Telemetry
+		None,
+		// Protocol ID
+		None,
+		// Properties
+		None,
+		None,
+		// Extensions
+		None,
+	))
+}
+
+/// A staging chain whose pre-registered claims are loaded from the JSON file at `claims_path`.
+/// It is run by the Alice and Bob development authorities, so it is a local chain, not a live one.
+pub fn staging_config(claims_path: &Path) -> Result<ChainSpec, String> {
+	let wasm_binary = WASM_BINARY.ok_or_else(|| "Staging wasm not available".to_string())?;
+	let claims = load_genesis_claims(claims_path)?;
+
+	Ok(ChainSpec::from_genesis(
+		// Name
+		"Staging Testnet",
+		// ID
+		"staging_testnet",
+		ChainType::Local,
+		move || {
+			testnet_genesis(
+				wasm_binary,
+				// Initial PoA authorities
+				vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
+				// Sudo account
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				// Pre-funded accounts
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+				],
+				// Pre-registered claims
+				claims.clone(),
 				true,
 			)
 		},
@@ -130,6 +212,7 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	initial_claims: Vec<(Vec<u8>, AccountId)>,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -152,5 +235,6 @@ fn testnet_genesis(
 			key: Some(root_key),
 		},
 		transaction_payment: Default::default(),
+		poe_module: PoeModuleConfig { claims: initial_claims },
 	}
 }
@@ -50,4 +50,8 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Proof-of-existence utilities.
+	#[command(subcommand)]
+	Poe(crate::poe::PoeSubcommand),
 }
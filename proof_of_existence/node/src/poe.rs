@@ -0,0 +1,104 @@
+//! The `poe` subcommands, which compute claim digests and look them up in the local database.
+
+use crate::service::FullClient;
+
+use node_template_runtime::{Hash, Runtime};
+use pallet_poe::{ClaimInfo, Claims};
+use sc_cli::{
+	BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, Result, SharedParams,
+};
+use sc_client_api::StorageProvider;
+use sp_blockchain::HeaderBackend;
+use sp_core::{storage::StorageKey, Decode};
+use std::path::{Path, PathBuf};
+
+/// Proof-of-existence utilities.
+#[derive(Debug, clap::Subcommand)]
+pub enum PoeSubcommand {
+	/// Print the digest under which a document is claimed.
+	Hash(HashCmd),
+
+	/// Look up the claim on a document in the local database.
+	Verify(VerifyCmd),
+}
+
+/// The `poe hash` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct HashCmd {
+	/// The document to hash.
+	pub file: PathBuf,
+}
+
+impl HashCmd {
+	/// Print the digest of the document.
+	pub fn run(&self) -> Result<()> {
+		println!("{:?}", file_digest(&self.file)?);
+		Ok(())
+	}
+}
+
+/// The `poe verify` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct VerifyCmd {
+	/// The document to verify.
+	pub file: PathBuf,
+
+	/// Block hash or number to verify the claim at, the best block by default.
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl VerifyCmd {
+	/// Print the owner and registration block of the claim on the document.
+	pub fn run(&self, client: &FullClient) -> Result<()> {
+		let digest = file_digest(&self.file)?;
+		let at = match &self.at {
+			Some(at) => client.expect_block_hash_from_id(&at.parse()?)?,
+			None => client.info().best_hash,
+		};
+
+		let key = StorageKey(Claims::<Runtime>::hashed_key_for(digest));
+		let info = match client.storage(at, &key)? {
+			Some(data) => ClaimInfo::<Runtime>::decode(&mut &data.0[..])
+				.map_err(|e| format!("failed to decode claim: {}", e))?,
+			None => return Err(format!("{:?} is not claimed at block {:?}", digest, at).into()),
+		};
+
+		println!("digest: {:?}", digest);
+		println!("owner: {}", info.owner);
+		println!("block: {}", info.block_number);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for VerifyCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// The digest the runtime stores the claim on the given document under.
+fn file_digest(file: &Path) -> Result<Hash> {
+	let document = std::fs::read(file)?;
+	Ok(pallet_poe::Pallet::<Runtime>::claim_digest(&document))
+}
@@ -7,46 +7,321 @@
 
 use std::sync::Arc;
 
-use jsonrpsee::RpcModule;
-use node_template_runtime::{opaque::Block, AccountId, Balance, Index};
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::{
+		error::{CallError, ErrorObject},
+		SubscriptionResult,
+	},
+	RpcModule, SubscriptionSink,
+};
+use node_rpc_events::spawn_event_subscription;
+use node_template_runtime::{
+	opaque::Block, AccountId, Balance, BlockNumber, Hash, Index, Runtime, RuntimeEvent,
+};
+use pallet_poe_runtime_api::PoeApi as PoeRuntimeApi;
+use sc_client_api::{Backend, BlockchainEvents};
+use sc_rpc::SubscriptionTaskExecutor;
 use sc_transaction_pool_api::TransactionPool;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_core::hexdisplay::HexDisplay;
 
 pub use sc_rpc_api::DenyUnsafe;
 
 /// Full client dependencies.
-pub struct FullDeps<C, P> {
+pub struct FullDeps<C, P, B> {
 	/// The client instance to use.
 	pub client: Arc<C>,
+	/// The backend the client reads state from.
+	pub backend: Arc<B>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Executor for subscription tasks.
+	pub subscription_executor: SubscriptionTaskExecutor,
+}
+
+/// Proof of existence RPC methods.
+#[rpc(client, server)]
+pub trait PoeApi {
+	/// The digests of all claims owned by `account`, at the given block or the best block.
+	#[method(name = "poe_claimsOf")]
+	fn claims_of(&self, account: AccountId, at: Option<Hash>) -> RpcResult<Vec<Hash>>;
+}
+
+/// Implements [`PoeApiServer`] by calling into the runtime.
+pub struct Poe<C> {
+	client: Arc<C>,
+}
+
+impl<C> Poe<C> {
+	/// Create a new [`Poe`] RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client }
+	}
+}
+
+impl<C> PoeApiServer for Poe<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: PoeRuntimeApi<Block, AccountId, BlockNumber, Hash>,
+{
+	fn claims_of(&self, account: AccountId, at: Option<Hash>) -> RpcResult<Vec<Hash>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client.runtime_api().claims_of(at, account).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				1,
+				"Unable to query claims of the account.",
+				Some(e.to_string()),
+			))
+			.into()
+		})
+	}
+}
+
+/// Which events [`PoeEventsApiServer::subscribe_events`] streams. Unset filters match any event.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventFilter {
+	/// Only events involving this account.
+	pub account: Option<AccountId>,
+	/// Only events about the claim with this digest. Events about raw claims match the digest
+	/// the claim is stored under.
+	pub claim: Option<Hash>,
+}
+
+impl EventFilter {
+	fn matches(&self, event: &EventNotification) -> bool {
+		self.account.as_ref().map_or(true, |account| event.accounts.contains(account)) &&
+			self.claim.map_or(true, |claim| event.digest == Some(claim))
+	}
+}
+
+/// A `pallet_poe` event, as streamed by [`PoeEventsApiServer::subscribe_events`].
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventNotification {
+	/// The block the event was deposited in.
+	pub block_hash: Hash,
+	/// The number of that block.
+	pub block_number: BlockNumber,
+	/// The event variant, e.g. `DigestClaimCreated`.
+	pub name: &'static str,
+	/// The event fields, named after the event documentation.
+	pub data: Value,
+	#[serde(skip)]
+	accounts: Vec<AccountId>,
+	#[serde(skip)]
+	digest: Option<Hash>,
+}
+
+impl EventNotification {
+	fn new(
+		block_hash: Hash,
+		block_number: BlockNumber,
+		event: pallet_poe::Event<Runtime>,
+	) -> Option<Self> {
+		use pallet_poe::Event::*;
+
+		let raw_claim = |claim: &[u8]| {
+			(json!(hex(claim)), Some(pallet_poe::Pallet::<Runtime>::claim_digest(claim)))
+		};
+
+		let (name, data, accounts, digest) = match event {
+			ClaimCreated(owner, claim) => {
+				let (claim, digest) = raw_claim(&claim);
+				("ClaimCreated", json!({ "owner": owner, "claim": claim }), vec![owner], digest)
+			},
+			ClaimRevoked(owner, claim) => {
+				let (claim, digest) = raw_claim(&claim);
+				("ClaimRevoked", json!({ "owner": owner, "claim": claim }), vec![owner], digest)
+			},
+			ClaimTransfered(from, to, claim) => {
+				let (claim, digest) = raw_claim(&claim);
+				let data = json!({ "from": from, "to": to, "claim": claim });
+				("ClaimTransfered", data, vec![from, to], digest)
+			},
+			DigestClaimCreated(owner, digest) => (
+				"DigestClaimCreated",
+				json!({ "owner": owner, "digest": digest }),
+				vec![owner],
+				Some(digest),
+			),
+			DigestClaimRevoked(owner, digest) => (
+				"DigestClaimRevoked",
+				json!({ "owner": owner, "digest": digest }),
+				vec![owner],
+				Some(digest),
+			),
+			DigestClaimTransfered(from, to, digest) => (
+				"DigestClaimTransfered",
+				json!({ "from": from, "to": to, "digest": digest }),
+				vec![from, to],
+				Some(digest),
+			),
+			ClaimRenewed(owner, digest, expires_at) => (
+				"ClaimRenewed",
+				json!({ "owner": owner, "digest": digest, "expiresAt": expires_at }),
+				vec![owner],
+				Some(digest),
+			),
+			ClaimExpired(owner, digest) => (
+				"ClaimExpired",
+				json!({ "owner": owner, "digest": digest }),
+				vec![owner],
+				Some(digest),
+			),
+			MerkleRootAnchored(owner, root, leaf_count) => (
+				"MerkleRootAnchored",
+				json!({ "owner": owner, "root": root, "leafCount": leaf_count }),
+				vec![owner],
+				Some(root),
+			),
+			MerkleRootRevoked(owner, root) => (
+				"MerkleRootRevoked",
+				json!({ "owner": owner, "root": root }),
+				vec![owner],
+				Some(root),
+			),
+			ClaimTransferProposed(from, to, digest, expires_at) => (
+				"ClaimTransferProposed",
+				json!({ "from": from, "to": to, "digest": digest, "expiresAt": expires_at }),
+				vec![from, to],
+				Some(digest),
+			),
+			ClaimTransferCancelled(who, digest) => (
+				"ClaimTransferCancelled",
+				json!({ "who": who, "digest": digest }),
+				vec![who],
+				Some(digest),
+			),
+			CoSignedClaimCreated(owner, digest, deadline) => (
+				"CoSignedClaimCreated",
+				json!({ "owner": owner, "digest": digest, "deadline": deadline }),
+				vec![owner],
+				Some(digest),
+			),
+			ClaimEndorsed(signer, digest) => (
+				"ClaimEndorsed",
+				json!({ "signer": signer, "digest": digest }),
+				vec![signer],
+				Some(digest),
+			),
+			ClaimFinalized(digest) =>
+				("ClaimFinalized", json!({ "digest": digest }), vec![], Some(digest)),
+			SignedClaimCreated(relayer, owner, digest) => (
+				"SignedClaimCreated",
+				json!({ "relayer": relayer, "owner": owner, "digest": digest }),
+				vec![relayer, owner],
+				Some(digest),
+			),
+			ClaimForceRevoked(owner, digest, reason) => (
+				"ClaimForceRevoked",
+				json!({ "owner": owner, "digest": digest, "reason": hex(&reason) }),
+				vec![owner],
+				Some(digest),
+			),
+			ClaimForceTransferred(from, to, digest, reason) => (
+				"ClaimForceTransferred",
+				json!({ "from": from, "to": to, "digest": digest, "reason": hex(&reason) }),
+				vec![from, to],
+				Some(digest),
+			),
+			_ => return None,
+		};
+
+		Some(Self { block_hash, block_number, name, data, accounts, digest })
+	}
+}
+
+fn hex(bytes: &[u8]) -> String {
+	format!("0x{}", HexDisplay::from(&bytes))
+}
+
+/// Proof of existence event subscriptions.
+#[rpc(server)]
+pub trait PoeEventsApi {
+	/// Stream the `pallet_poe` events of every new best block that pass `filter`.
+	#[subscription(
+		name = "poe_subscribeEvents" => "poe_event",
+		unsubscribe = "poe_unsubscribeEvents",
+		item = EventNotification,
+	)]
+	fn subscribe_events(&self, filter: Option<EventFilter>);
+}
+
+/// Implements [`PoeEventsApiServer`] from block import notifications.
+pub struct PoeEvents<C, B> {
+	client: Arc<C>,
+	backend: Arc<B>,
+	executor: SubscriptionTaskExecutor,
+}
+
+impl<C, B> PoeEvents<C, B> {
+	/// Create a new [`PoeEvents`] RPC handler.
+	pub fn new(client: Arc<C>, backend: Arc<B>, executor: SubscriptionTaskExecutor) -> Self {
+		Self { client, backend, executor }
+	}
+}
+
+impl<C, B> PoeEventsApiServer for PoeEvents<C, B>
+where
+	C: BlockchainEvents<Block> + Send + Sync + 'static,
+	B: Backend<Block> + Send + Sync + 'static,
+{
+	fn subscribe_events(
+		&self,
+		sink: SubscriptionSink,
+		filter: Option<EventFilter>,
+	) -> SubscriptionResult {
+		let filter = filter.unwrap_or_default();
+		spawn_event_subscription::<Block, RuntimeEvent, _, _, _, _>(
+			"poe-rpc-subscription",
+			&*self.client,
+			self.backend.clone(),
+			&self.executor,
+			sink,
+			move |hash, number, event| match event {
+				RuntimeEvent::PoeModule(event) => EventNotification::new(hash, number, event)
+					.filter(|event| filter.matches(event)),
+				_ => None,
+			},
+		);
+		Ok(())
+	}
 }
 
 /// Instantiate all full RPC extensions.
-pub fn create_full<C, P>(
-	deps: FullDeps<C, P>,
+pub fn create_full<C, P, B>(
+	deps: FullDeps<C, P, B>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
 	C: ProvideRuntimeApi<Block>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + 'static,
-	C: Send + Sync + 'static,
+	C: BlockchainEvents<Block> + Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BlockBuilder<Block>,
+	C::Api: PoeRuntimeApi<Block, AccountId, BlockNumber, Hash>,
 	P: TransactionPool + 'static,
+	B: Backend<Block> + Send + Sync + 'static,
 {
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps { client, backend, pool, deny_unsafe, subscription_executor } = deps;
 
 	module.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
-	module.merge(TransactionPayment::new(client).into_rpc())?;
+	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	module.merge(Poe::new(client.clone()).into_rpc())?;
+	module.merge(PoeEvents::new(client, backend, subscription_executor).into_rpc())?;
 
 	// Extend this RPC with a custom API by using the following syntax.
 	// `YourRpcStruct` should have a reference to a client, which is needed
@@ -0,0 +1,31 @@
+//! Runtime API definition for the proof of existence pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_poe::{EndorsementStatus, ProvenanceRecord};
+
+sp_api::decl_runtime_apis! {
+	/// Queries over the claims registered with `pallet_poe`.
+	pub trait PoeApi<AccountId, BlockNumber, Hash> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Hash: Codec,
+	{
+		/// The chain of custody of the claim with the given digest, oldest record first.
+		fn claim_history(digest: Hash) -> Vec<ProvenanceRecord<AccountId, BlockNumber>>;
+
+		/// Whether `proof` proves that `leaf` is the leaf at `index` of the tree with the
+		/// anchored `root`.
+		fn verify_merkle_proof(root: Hash, leaf: Hash, index: u32, proof: Vec<Hash>) -> bool;
+
+		/// The digests of all claims owned by `account`.
+		fn claims_of(account: AccountId) -> Vec<Hash>;
+
+		/// The endorsement status of the co-signed claim with the given digest, or `None` if
+		/// the claim does not require endorsements.
+		fn endorsement_status(digest: Hash) -> Option<EndorsementStatus<AccountId, BlockNumber>>;
+	}
+}
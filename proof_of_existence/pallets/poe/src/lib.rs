@@ -5,6 +5,8 @@
 /// <https://docs.substrate.io/reference/frame-pallets/>
 pub use pallet::*;
 
+mod migrations;
+
 #[cfg(test)]
 mod mock;
 
@@ -17,10 +19,198 @@ mod benchmarking;
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use frame_support::pallet_prelude::*;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{BalanceStatus, Currency, ReservableCurrency},
+	};
 	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::{
+		Hash, IdentifyAccount, One, SaturatedConversion, Saturating, Verify, Zero,
+	};
+	use sp_std::prelude::*;
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// A registered claim, keyed in storage by its digest.
+	#[derive(
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		RuntimeDebugNoBound,
+		Encode,
+		Decode,
+		TypeInfo,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct ClaimInfo<T: Config> {
+		/// The current owner of the claim.
+		pub owner: T::AccountId,
+		/// The block in which the claim was registered or last transferred.
+		pub block_number: T::BlockNumber,
+		/// An optional description of the notarized document.
+		pub description: Option<BoundedVec<u8, T::MaxDescriptionLength>>,
+		/// The raw claim bytes, if the claim was registered in raw mode.
+		pub raw: Option<BoundedVec<u8, T::MaxClaimLength>>,
+	}
+
+	/// An entry in the chain of custody of a claim.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum ProvenanceRecord<AccountId, BlockNumber> {
+		/// The claim was registered by `who`.
+		Created { who: AccountId, block_number: BlockNumber },
+		/// The claim was transferred from `from` to `to`.
+		Transferred { from: AccountId, to: AccountId, block_number: BlockNumber },
+		/// The claim was revoked by its owner `who`.
+		Revoked { who: AccountId, block_number: BlockNumber },
+		/// The claim reached the end of its validity period and was removed.
+		Expired { block_number: BlockNumber },
+		/// The claim was removed by `ForceOrigin`.
+		ForceRevoked { block_number: BlockNumber },
+	}
+
+	/// A Merkle root anchoring a batch of document digests.
+	#[derive(
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		RuntimeDebugNoBound,
+		Encode,
+		Decode,
+		TypeInfo,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct MerkleAnchor<T: Config> {
+		/// The account that anchored the root.
+		pub owner: T::AccountId,
+		/// The block in which the root was anchored.
+		pub block_number: T::BlockNumber,
+		/// The number of leaves in the tree.
+		pub leaf_count: u32,
+		/// The deposit reserved from the owner until the root is revoked.
+		pub deposit: BalanceOf<T>,
+	}
+
+	/// A transfer of a claim awaiting acceptance by its recipient.
+	#[derive(
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		RuntimeDebugNoBound,
+		Encode,
+		Decode,
+		TypeInfo,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct PendingTransfer<T: Config> {
+		/// The owner proposing the transfer.
+		pub from: T::AccountId,
+		/// The account that has to accept the transfer.
+		pub to: T::AccountId,
+		/// The block from which the proposal can no longer be accepted.
+		pub expires_at: T::BlockNumber,
+	}
+
+	/// The parties that have to endorse a co-signed claim.
+	#[derive(
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		RuntimeDebugNoBound,
+		Encode,
+		Decode,
+		TypeInfo,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct CoSigners<T: Config> {
+		/// The accounts whose endorsement is required.
+		pub required: BoundedVec<T::AccountId, T::MaxSigners>,
+		/// The accounts that have endorsed the claim so far.
+		pub endorsed: BoundedVec<T::AccountId, T::MaxSigners>,
+		/// The block from which endorsements are no longer accepted.
+		pub deadline: T::BlockNumber,
+	}
+
+	/// The endorsement status of a co-signed claim, as returned by the runtime API.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct EndorsementStatus<AccountId, BlockNumber> {
+		/// The accounts whose endorsement is required.
+		pub required: Vec<AccountId>,
+		/// The accounts that have endorsed the claim so far.
+		pub endorsed: Vec<AccountId>,
+		/// The block from which endorsements are no longer accepted.
+		pub deadline: BlockNumber,
+		/// Whether every required account endorsed the claim before the deadline.
+		pub finalized: bool,
+	}
+
+	/// How a signed claim payload is meant to reach the chain.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum ClaimSubmission {
+		/// Submitted by a relayer through `create_claim_signed`.
+		Relayed,
+		/// Submitted as an unsigned transaction through `create_claim_unsigned`.
+		Unsigned,
+	}
+
+	/// A claim signed off-chain by its owner, to be submitted on their behalf.
+	///
+	/// The owner signs the SCALE encoding of this payload. The genesis hash and submission
+	/// path keep a signed payload from being replayed on another chain or through the other
+	/// call.
+	#[derive(
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		RuntimeDebugNoBound,
+		Encode,
+		Decode,
+		TypeInfo,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct ClaimPayload<T: Config> {
+		/// The digest of the notarized document.
+		pub digest: T::Hash,
+		/// An optional description of the notarized document.
+		pub description: Option<BoundedVec<u8, T::MaxDescriptionLength>>,
+		/// The owner's next nonce, see [`Nonces`].
+		pub nonce: u64,
+		/// The hash of the genesis block of the chain the payload is meant for.
+		pub genesis_hash: T::Hash,
+		/// The call the payload is meant to be submitted through.
+		pub submission: ClaimSubmission,
+	}
+
+	pub type ProvenanceRecordOf<T> = ProvenanceRecord<
+		<T as frame_system::Config>::AccountId,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+	/// `InvalidTransaction::Custom` code of unsigned claims submitted before the signer's
+	/// `UnsignedInterval` has passed.
+	pub const UNSIGNED_RATE_LIMITED: u8 = 1;
+
+	/// `InvalidTransaction::Custom` code of unsigned claims of a digest that is already claimed.
+	pub const UNSIGNED_CLAIM_EXISTS: u8 = 2;
+
+	/// `InvalidTransaction::Custom` code of unsigned claims of a signer that already owns
+	/// `MaxClaimsPerOwner` claims.
+	pub const UNSIGNED_TOO_MANY_CLAIMS: u8 = 3;
 
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
@@ -30,6 +220,55 @@ pub mod pallet {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		/// The Maximum length of calim that can be added
 		type MaxClaimLength: Get<u32>;
+		/// The maximum length of a claim description.
+		#[pallet::constant]
+		type MaxDescriptionLength: Get<u32>;
+		/// The currency in which claim deposits are reserved.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// The deposit reserved per byte of a claim and its description.
+		#[pallet::constant]
+		type ClaimDeposit: Get<BalanceOf<Self>>;
+		/// The maximum number of provenance records kept per claim.
+		///
+		/// Once reached, the oldest record after the creation record is dropped.
+		#[pallet::constant]
+		type MaxHistoryLength: Get<u32>;
+		/// The maximum number of claims that can expire in the same block.
+		#[pallet::constant]
+		type MaxExpiriesPerBlock: Get<u32>;
+		/// The maximum number of claims registered by a single `create_claims_batch` call.
+		#[pallet::constant]
+		type MaxBatchSize: Get<u32>;
+		/// The maximum number of claims a single account can own.
+		#[pallet::constant]
+		type MaxClaimsPerOwner: Get<u32>;
+		/// Whether owners may transfer claims without the recipient accepting them.
+		///
+		/// When disabled, claims can only change hands through `propose_claim_transfer` and
+		/// `accept_claim_transfer`.
+		#[pallet::constant]
+		type AllowDirectTransfer: Get<bool>;
+		/// The number of blocks a proposed transfer can be accepted for.
+		#[pallet::constant]
+		type TransferProposalPeriod: Get<Self::BlockNumber>;
+		/// The maximum number of parties that can be required to endorse a claim.
+		#[pallet::constant]
+		type MaxSigners: Get<u32>;
+		/// The signature owners use to sign claims submitted on their behalf.
+		type OffchainSignature: Verify<Signer = Self::OffchainPublic> + Parameter;
+		/// The public key that verifies an `OffchainSignature`.
+		type OffchainPublic: IdentifyAccount<AccountId = Self::AccountId>;
+		/// The priority of unsigned claim transactions.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+		/// The minimum number of blocks between two unsigned claims of the same account.
+		#[pallet::constant]
+		type UnsignedInterval: Get<Self::BlockNumber>;
+		/// The origin allowed to revoke and transfer any claim, e.g. to remove illegal content.
+		type ForceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// The maximum length of the reason given for a forced revocation or transfer.
+		#[pallet::constant]
+		type MaxReasonLength: Get<u32>;
 	}
 
 	// The pallet's runtime storage items.
@@ -45,6 +284,171 @@ pub mod pallet {
 		(T::AccountId, T::BlockNumber),
 	>;
 
+	/// All registered claims, keyed by the `T::Hashing` digest of the document.
+	///
+	/// Raw claims are registered here under the digest of their bytes as well, so this map is
+	/// the single source of truth while `Proofs` is kept for compatibility.
+	#[pallet::storage]
+	#[pallet::getter(fn claims)]
+	pub type Claims<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, ClaimInfo<T>>;
+
+	/// The deposit reserved from the owner of each claim.
+	///
+	/// Claims registered at genesis or before deposits were introduced hold no deposit.
+	#[pallet::storage]
+	#[pallet::getter(fn deposits)]
+	pub type Deposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, BalanceOf<T>, ValueQuery>;
+
+	/// The digests of the claims owned by each account.
+	#[pallet::storage]
+	pub type ClaimsByOwner<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::Hash,
+		(),
+		OptionQuery,
+	>;
+
+	/// The number of claims owned by each account, bounded by `MaxClaimsPerOwner`.
+	#[pallet::storage]
+	#[pallet::getter(fn owner_claim_count)]
+	pub type OwnerClaimCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// The chain of custody of each claim, oldest record first.
+	///
+	/// The history of a removed claim ends with how it was removed, and is kept until the digest
+	/// is registered again.
+	#[pallet::storage]
+	#[pallet::getter(fn history)]
+	pub type History<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,
+		BoundedVec<ProvenanceRecordOf<T>, T::MaxHistoryLength>,
+		ValueQuery,
+	>;
+
+	/// The block at which a claim expires, for claims with a validity period.
+	#[pallet::storage]
+	#[pallet::getter(fn claim_expiry)]
+	pub type ClaimExpiry<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, T::BlockNumber>;
+
+	/// The claims expiring at each block, drained by `on_idle`.
+	#[pallet::storage]
+	pub type ExpiryQueue<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<T::Hash, T::MaxExpiriesPerBlock>,
+		ValueQuery,
+	>;
+
+	/// The next block of `ExpiryQueue` to be drained.
+	///
+	/// Starts at the genesis block, or at the block of the upgrade that introduced it.
+	#[pallet::storage]
+	pub type ExpiryCursor<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// Merkle roots anchoring batches of documents, keyed by root.
+	#[pallet::storage]
+	#[pallet::getter(fn merkle_roots)]
+	pub type MerkleRoots<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, MerkleAnchor<T>>;
+
+	/// Proposed transfers awaiting acceptance, keyed by claim digest.
+	///
+	/// A proposal is dropped once the claim changes hands or is removed, and by `on_idle` once
+	/// it expires.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_transfers)]
+	pub type PendingTransfers<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, PendingTransfer<T>>;
+
+	/// The digests of the claims whose proposed transfer expires at each block, drained by
+	/// `on_idle`.
+	#[pallet::storage]
+	pub type TransferExpiries<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Blake2_128Concat,
+		T::Hash,
+		(),
+		OptionQuery,
+	>;
+
+	/// The next block of `TransferExpiries` to be drained.
+	///
+	/// Starts at the genesis block, or at the block of the upgrade that introduced it.
+	#[pallet::storage]
+	pub type TransferExpiryCursor<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// The parties required to endorse each co-signed claim.
+	///
+	/// A co-signed claim is finalized once every required party has endorsed it.
+	#[pallet::storage]
+	#[pallet::getter(fn co_signers)]
+	pub type CoSignedClaims<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, CoSigners<T>>;
+
+	/// The nonce the next signed claim payload of each account must carry.
+	#[pallet::storage]
+	#[pallet::getter(fn nonces)]
+	pub type Nonces<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u64, ValueQuery>;
+
+	/// The block in which each account last registered a claim through the unsigned path.
+	#[pallet::storage]
+	#[pallet::getter(fn last_unsigned_claim)]
+	pub type LastUnsignedClaim<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Claims registered at genesis, as `(claim, owner)` pairs.
+		pub claims: Vec<(Vec<u8>, T::AccountId)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { claims: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			ExpiryCursor::<T>::put(frame_system::Pallet::<T>::block_number());
+			TransferExpiryCursor::<T>::put(frame_system::Pallet::<T>::block_number());
+
+			for (claim, owner) in &self.claims {
+				let claim: BoundedVec<u8, T::MaxClaimLength> =
+					claim.clone().try_into().expect("genesis claim is longer than MaxClaimLength");
+				let digest = Pallet::<T>::claim_digest(&claim);
+				assert!(!Claims::<T>::contains_key(digest), "duplicate claim in genesis");
+
+				let block_number = frame_system::Pallet::<T>::block_number();
+				Pallet::<T>::insert_claim(
+					&digest,
+					&ClaimInfo {
+						owner: owner.clone(),
+						block_number,
+						description: None,
+						raw: Some(claim),
+					},
+				);
+				Pallet::<T>::record_provenance(
+					&digest,
+					ProvenanceRecord::Created { who: owner.clone(), block_number },
+				);
+				Pallet::<T>::index_claim(owner, &digest)
+					.expect("genesis account owns more than MaxClaimsPerOwner claims");
+			}
+		}
+	}
+
 	// Pallets use events to inform users when important changes are made.
 	// https://docs.substrate.io/main-docs/build/events-errors/
 	#[pallet::event]
@@ -53,7 +457,43 @@ pub mod pallet {
 		/// Event documentation should end with an array that provides descriptive names for event
 		ClaimCreated(T::AccountId, BoundedVec<u8, T::MaxClaimLength>),
 		ClaimRevoked(T::AccountId, BoundedVec<u8, T::MaxClaimLength>),
-		ClaimTransfered(T::AccountId, BoundedVec<u8, T::MaxClaimLength>),
+		/// [from, to, claim]
+		ClaimTransfered(T::AccountId, T::AccountId, BoundedVec<u8, T::MaxClaimLength>),
+		/// A claim was registered by its digest. [owner, digest]
+		DigestClaimCreated(T::AccountId, T::Hash),
+		/// A digest claim was revoked. [owner, digest]
+		DigestClaimRevoked(T::AccountId, T::Hash),
+		/// A digest claim was transferred. [from, to, digest]
+		DigestClaimTransfered(T::AccountId, T::AccountId, T::Hash),
+		/// The validity period of a claim was changed. [owner, digest, expires_at]
+		ClaimRenewed(T::AccountId, T::Hash, Option<T::BlockNumber>),
+		/// A claim reached the end of its validity period and was removed. [owner, digest]
+		ClaimExpired(T::AccountId, T::Hash),
+		/// A Merkle root was anchored. [owner, root, leaf_count]
+		MerkleRootAnchored(T::AccountId, T::Hash, u32),
+		/// A transfer of a claim was proposed. [from, to, digest, expires_at]
+		ClaimTransferProposed(T::AccountId, T::AccountId, T::Hash, T::BlockNumber),
+		/// A proposed transfer was cancelled by either party. [who, digest]
+		ClaimTransferCancelled(T::AccountId, T::Hash),
+		/// A co-signed claim was registered. [owner, digest, deadline]
+		CoSignedClaimCreated(T::AccountId, T::Hash, T::BlockNumber),
+		/// A required party endorsed a co-signed claim. [signer, digest]
+		ClaimEndorsed(T::AccountId, T::Hash),
+		/// Every required party endorsed a co-signed claim. [digest]
+		ClaimFinalized(T::Hash),
+		/// A claim signed by its owner was submitted by a relayer. [relayer, owner, digest]
+		SignedClaimCreated(T::AccountId, T::AccountId, T::Hash),
+		/// A claim was revoked by `ForceOrigin`. [owner, digest, reason]
+		ClaimForceRevoked(T::AccountId, T::Hash, BoundedVec<u8, T::MaxReasonLength>),
+		/// A claim was transferred by `ForceOrigin`. [from, to, digest, reason]
+		ClaimForceTransferred(
+			T::AccountId,
+			T::AccountId,
+			T::Hash,
+			BoundedVec<u8, T::MaxReasonLength>,
+		),
+		/// An anchored Merkle root was revoked. [owner, root]
+		MerkleRootRevoked(T::AccountId, T::Hash),
 	}
 
 	// Errors inform users that something went wrong.
@@ -65,6 +505,69 @@ pub mod pallet {
 		ClaimTooLong,
 		ClaimNotExist,
 		NotClaimOwner,
+		/// The expiry block must be in the future.
+		InvalidExpiry,
+		/// Too many claims already expire in the requested block.
+		TooManyExpiries,
+		/// The account already owns `MaxClaimsPerOwner` claims.
+		TooManyClaims,
+		/// The claim has expired.
+		ClaimHasExpired,
+		/// The Merkle root has already been anchored.
+		RootAlreadyAnchored,
+		/// A Merkle tree must have at least one leaf.
+		EmptyMerkleTree,
+		/// Direct transfers are disabled; propose the transfer instead.
+		DirectTransferDisabled,
+		/// No transfer of the claim has been proposed, or none involving the caller.
+		NoPendingTransfer,
+		/// Only the recipient of a proposed transfer can accept it.
+		NotTransferRecipient,
+		/// The proposed transfer is no longer valid.
+		TransferProposalExpired,
+		/// A co-signed claim needs at least one signer.
+		NoSigners,
+		/// A signer was listed more than once.
+		DuplicateSigner,
+		/// The claim does not require endorsements.
+		NotCoSignedClaim,
+		/// The caller is not one of the required signers of the claim.
+		NotRequiredSigner,
+		/// The caller has already endorsed the claim.
+		AlreadyEndorsed,
+		/// The endorsement deadline of the claim has passed.
+		EndorsementDeadlinePassed,
+		/// The signature does not match the payload and signer.
+		InvalidSignature,
+		/// The payload nonce is not the signer's next nonce.
+		InvalidNonce,
+		/// The signer registered an unsigned claim less than `UnsignedInterval` blocks ago.
+		UnsignedRateLimited,
+		/// The payload was signed for another chain.
+		WrongGenesis,
+		/// The payload was signed for the other submission path.
+		WrongSubmission,
+		/// The Merkle root has not been anchored.
+		RootNotAnchored,
+		/// Only the account that anchored a Merkle root can revoke it.
+		NotRootOwner,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			migrations::v1::migrate::<T>()
+				.saturating_add(migrations::v2::migrate::<T>())
+				.saturating_add(migrations::v3::migrate::<T>())
+		}
+
+		fn on_idle(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			let used = Self::expire_claims(now, remaining_weight);
+			used.saturating_add(Self::expire_transfer_proposals(
+				now,
+				remaining_weight.saturating_sub(used),
+			))
+		}
 	}
 
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
@@ -74,11 +577,11 @@ pub mod pallet {
 	impl<T: Config> Pallet<T> {
 		/// An example dispatchable that takes a singles value as a parameter, writes the value to
 		/// storage and emits an event. This function must be dispatched by a signed extrinsic.
-		
+
 		#[pallet::call_index(0)]
 		#[pallet::weight(0)]
 		pub fn create_claim(
-			origin: OriginFor<T>, 
+			origin: OriginFor<T>,
 			claim: BoundedVec<u8, T::MaxClaimLength>,
 		) -> DispatchResultWithPostInfo {
 			// Check that the extrinsic was signed and get the signer.
@@ -86,13 +589,9 @@ pub mod pallet {
 			// https://docs.substrate.io/main-docs/build/origins/
 			let sender = ensure_signed(origin)?;
 
-			ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExists);
-
 			// Update storage.
-			Proofs::<T>::insert(
-				&claim,
-				(sender.clone(), frame_system::Pallet::<T>::block_number()),
-			);
+			let digest = Self::claim_digest(&claim);
+			Self::do_create_claim(&sender, digest, None, Some(claim.clone()), None)?;
 
 			// Emit an event.
 			Self::deposit_event(Event::ClaimCreated(sender, claim));
@@ -104,7 +603,7 @@ pub mod pallet {
 		#[pallet::call_index(1)]
 		#[pallet::weight(0)]
 		pub fn revoke_claim(
-			origin: OriginFor<T>, 
+			origin: OriginFor<T>,
 			claim: BoundedVec<u8, T::MaxClaimLength>,
 		) -> DispatchResultWithPostInfo {
 			// Check that the extrinsic was signed and get the signer.
@@ -112,13 +611,8 @@ pub mod pallet {
 			// https://docs.substrate.io/main-docs/build/origins/
 			let sender = ensure_signed(origin)?;
 
-			let (owner, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
-			ensure!(owner == sender, Error::<T>::NotClaimOwner);
-
 			// Update storage.
-			Proofs::<T>::remove(
-				&claim,
-			);
+			Self::do_revoke_claim(&sender, Self::claim_digest(&claim))?;
 
 			// Emit an event.
 			Self::deposit_event(Event::ClaimRevoked(sender, claim));
@@ -129,7 +623,7 @@ pub mod pallet {
 		#[pallet::call_index(2)]
 		#[pallet::weight(0)]
 		pub fn transfer_claim(
-			origin: OriginFor<T>, 
+			origin: OriginFor<T>,
 			dest: T::AccountId,
 			claim: BoundedVec<u8, T::MaxClaimLength>,
 		) -> DispatchResultWithPostInfo {
@@ -137,21 +631,834 @@ pub mod pallet {
 			// This function will return an error if the extrinsic is not signed.
 			// https://docs.substrate.io/main-docs/build/origins/
 			let sender = ensure_signed(origin)?;
-
-			let (owner, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
-			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(T::AllowDirectTransfer::get(), Error::<T>::DirectTransferDisabled);
 
 			// Update storage.
-			Proofs::<T>::insert(
-				&claim,
-				(dest.clone(), frame_system::Pallet::<T>::block_number()),
-			);
+			Self::do_transfer_claim(&sender, &dest, Self::claim_digest(&claim))?;
 
 			// Emit an event.
-			Self::deposit_event(Event::ClaimTransfered(dest, claim));
+			Self::deposit_event(Event::ClaimTransfered(sender, dest, claim));
 			// Return a successful DispatchResultWithPostInfo
 			Ok(().into())
 		}
 
+		/// Register a claim by the digest of a document of arbitrary size, with an optional
+		/// description and an optional block at which the claim expires.
+		#[pallet::call_index(3)]
+		#[pallet::weight(0)]
+		pub fn create_digest_claim(
+			origin: OriginFor<T>,
+			digest: T::Hash,
+			description: Option<BoundedVec<u8, T::MaxDescriptionLength>>,
+			expires_at: Option<T::BlockNumber>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			Self::do_create_claim(&sender, digest, description, None, expires_at)?;
+
+			Self::deposit_event(Event::DigestClaimCreated(sender, digest));
+			Ok(().into())
+		}
+
+		/// Revoke a claim by its digest.
+		#[pallet::call_index(4)]
+		#[pallet::weight(0)]
+		pub fn revoke_digest_claim(
+			origin: OriginFor<T>,
+			digest: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			Self::do_revoke_claim(&sender, digest)?;
+
+			Self::deposit_event(Event::DigestClaimRevoked(sender, digest));
+			Ok(().into())
+		}
+
+		/// Transfer a claim, identified by its digest, to `dest`.
+		#[pallet::call_index(5)]
+		#[pallet::weight(0)]
+		pub fn transfer_digest_claim(
+			origin: OriginFor<T>,
+			dest: T::AccountId,
+			digest: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			ensure!(T::AllowDirectTransfer::get(), Error::<T>::DirectTransferDisabled);
+
+			Self::do_transfer_claim(&sender, &dest, digest)?;
+
+			Self::deposit_event(Event::DigestClaimTransfered(sender, dest, digest));
+			Ok(().into())
+		}
+
+		/// Set the block at which a claim expires, or `None` to make it valid indefinitely.
+		#[pallet::call_index(6)]
+		#[pallet::weight(0)]
+		pub fn renew_claim(
+			origin: OriginFor<T>,
+			digest: T::Hash,
+			expires_at: Option<T::BlockNumber>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let info = Claims::<T>::get(digest).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(info.owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(!Self::is_expired(&digest), Error::<T>::ClaimHasExpired);
+
+			Self::unschedule_expiry(&digest);
+			if let Some(expires_at) = expires_at {
+				Self::schedule_expiry(&digest, expires_at)?;
+			}
+
+			Self::deposit_event(Event::ClaimRenewed(sender, digest, expires_at));
+			Ok(().into())
+		}
+
+		/// Register a batch of digest claims. Either every claim is registered or none is.
+		///
+		/// Each claim may first clear an expired claim of the same digest, so it is weighed as
+		/// the removal of a claim followed by its registration.
+		#[pallet::call_index(7)]
+		#[pallet::weight(
+			T::DbWeight::get().reads_writes(14, 19).saturating_mul(claims.len() as u64)
+		)]
+		pub fn create_claims_batch(
+			origin: OriginFor<T>,
+			claims: BoundedVec<
+				(T::Hash, Option<BoundedVec<u8, T::MaxDescriptionLength>>),
+				T::MaxBatchSize,
+			>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			for (digest, description) in claims {
+				Self::do_create_claim(&sender, digest, description, None, None)?;
+				Self::deposit_event(Event::DigestClaimCreated(sender.clone(), digest));
+			}
+
+			Ok(().into())
+		}
+
+		/// Anchor the root of a Merkle tree over `leaf_count` document digests, reserving the
+		/// deposit of a digest claim until the root is revoked.
+		///
+		/// Inclusion of a document is then proven off-chain with
+		/// [`Pallet::verify_merkle_proof`].
+		#[pallet::call_index(8)]
+		#[pallet::weight(0)]
+		pub fn anchor_merkle_root(
+			origin: OriginFor<T>,
+			root: T::Hash,
+			leaf_count: u32,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(leaf_count > 0, Error::<T>::EmptyMerkleTree);
+			ensure!(!MerkleRoots::<T>::contains_key(root), Error::<T>::RootAlreadyAnchored);
+
+			let deposit = Self::anchor_deposit(&root);
+			T::Currency::reserve(&sender, deposit)?;
+			MerkleRoots::<T>::insert(
+				root,
+				MerkleAnchor {
+					owner: sender.clone(),
+					block_number: frame_system::Pallet::<T>::block_number(),
+					leaf_count,
+					deposit,
+				},
+			);
+
+			Self::deposit_event(Event::MerkleRootAnchored(sender, root, leaf_count));
+			Ok(().into())
+		}
+
+		/// Propose to transfer a claim to `dest`, which takes effect once `dest` accepts it
+		/// within `TransferProposalPeriod` blocks. Replaces any earlier proposal for the claim.
+		#[pallet::call_index(9)]
+		#[pallet::weight(0)]
+		pub fn propose_claim_transfer(
+			origin: OriginFor<T>,
+			dest: T::AccountId,
+			digest: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let info = Claims::<T>::get(digest).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(info.owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(!Self::is_expired(&digest), Error::<T>::ClaimHasExpired);
+
+			let expires_at = frame_system::Pallet::<T>::block_number()
+				.saturating_add(T::TransferProposalPeriod::get());
+			Self::take_pending_transfer(&digest);
+			PendingTransfers::<T>::insert(
+				digest,
+				PendingTransfer { from: sender.clone(), to: dest.clone(), expires_at },
+			);
+			TransferExpiries::<T>::insert(expires_at, digest, ());
+
+			Self::deposit_event(Event::ClaimTransferProposed(sender, dest, digest, expires_at));
+			Ok(().into())
+		}
+
+		/// Accept a transfer of a claim proposed to the caller.
+		#[pallet::call_index(10)]
+		#[pallet::weight(0)]
+		pub fn accept_claim_transfer(
+			origin: OriginFor<T>,
+			digest: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let pending =
+				PendingTransfers::<T>::get(digest).ok_or(Error::<T>::NoPendingTransfer)?;
+			ensure!(pending.to == sender, Error::<T>::NotTransferRecipient);
+			ensure!(
+				pending.expires_at > frame_system::Pallet::<T>::block_number(),
+				Error::<T>::TransferProposalExpired
+			);
+
+			Self::do_transfer_claim(&pending.from, &sender, digest)?;
+
+			Self::deposit_event(Event::DigestClaimTransfered(pending.from, sender, digest));
+			Ok(().into())
+		}
+
+		/// Cancel a proposed transfer. Either the owner or the recipient may cancel it.
+		#[pallet::call_index(11)]
+		#[pallet::weight(0)]
+		pub fn cancel_claim_transfer(
+			origin: OriginFor<T>,
+			digest: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let pending =
+				PendingTransfers::<T>::get(digest).ok_or(Error::<T>::NoPendingTransfer)?;
+			ensure!(pending.from == sender || pending.to == sender, Error::<T>::NoPendingTransfer);
+			Self::take_pending_transfer(&digest);
+
+			Self::deposit_event(Event::ClaimTransferCancelled(sender, digest));
+			Ok(().into())
+		}
+
+		/// Register a claim that is finalized once every account in `signers` has endorsed it
+		/// with `endorse_claim` before `deadline`.
+		#[pallet::call_index(12)]
+		#[pallet::weight(0)]
+		pub fn create_cosigned_claim(
+			origin: OriginFor<T>,
+			digest: T::Hash,
+			description: Option<BoundedVec<u8, T::MaxDescriptionLength>>,
+			signers: BoundedVec<T::AccountId, T::MaxSigners>,
+			deadline: T::BlockNumber,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(!signers.is_empty(), Error::<T>::NoSigners);
+			ensure!(
+				deadline > frame_system::Pallet::<T>::block_number(),
+				Error::<T>::EndorsementDeadlinePassed
+			);
+			for (i, signer) in signers.iter().enumerate() {
+				ensure!(!signers[..i].contains(signer), Error::<T>::DuplicateSigner);
+			}
+
+			Self::do_create_claim(&sender, digest, description, None, None)?;
+			CoSignedClaims::<T>::insert(
+				digest,
+				CoSigners { required: signers, endorsed: Default::default(), deadline },
+			);
+
+			Self::deposit_event(Event::CoSignedClaimCreated(sender, digest, deadline));
+			Ok(().into())
+		}
+
+		/// Endorse a co-signed claim the caller is a required signer of.
+		#[pallet::call_index(13)]
+		#[pallet::weight(0)]
+		pub fn endorse_claim(origin: OriginFor<T>, digest: T::Hash) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let finalized = CoSignedClaims::<T>::try_mutate(digest, |co_signers| {
+				let co_signers = co_signers.as_mut().ok_or(Error::<T>::NotCoSignedClaim)?;
+				ensure!(co_signers.required.contains(&sender), Error::<T>::NotRequiredSigner);
+				ensure!(!co_signers.endorsed.contains(&sender), Error::<T>::AlreadyEndorsed);
+				ensure!(
+					co_signers.deadline > frame_system::Pallet::<T>::block_number(),
+					Error::<T>::EndorsementDeadlinePassed
+				);
+
+				// Both vectors share the `MaxSigners` bound and signers are unique.
+				co_signers
+					.endorsed
+					.try_push(sender.clone())
+					.map_err(|_| Error::<T>::AlreadyEndorsed)?;
+				Ok::<_, Error<T>>(co_signers.endorsed.len() == co_signers.required.len())
+			})?;
+
+			Self::deposit_event(Event::ClaimEndorsed(sender, digest));
+			if finalized {
+				Self::deposit_event(Event::ClaimFinalized(digest));
+			}
+			Ok(().into())
+		}
+
+		/// Register a claim signed off-chain by `signer`, who becomes its owner.
+		///
+		/// The caller only pays the transaction fee: the claim deposit is reserved from the
+		/// signer, so the signer must hold enough free balance for it. Each payload carries the
+		/// signer's next nonce, so it can only be submitted once.
+		#[pallet::call_index(14)]
+		#[pallet::weight(0)]
+		pub fn create_claim_signed(
+			origin: OriginFor<T>,
+			payload: ClaimPayload<T>,
+			signer: T::AccountId,
+			signature: T::OffchainSignature,
+		) -> DispatchResultWithPostInfo {
+			let relayer = ensure_signed(origin)?;
+
+			Self::check_claim_signature(&payload, &signer, &signature, ClaimSubmission::Relayed)?;
+			let digest = payload.digest;
+			Self::do_create_signed_claim(&signer, payload)?;
+
+			Self::deposit_event(Event::SignedClaimCreated(relayer, signer, digest));
+			Ok(().into())
+		}
+
+		/// Register a claim signed off-chain by `signer` through an unsigned transaction.
+		///
+		/// As with `create_claim_signed`, the claim deposit is reserved from the signer. Each
+		/// account can register at most one claim per `UnsignedInterval` blocks this way.
+		#[pallet::call_index(15)]
+		#[pallet::weight(0)]
+		pub fn create_claim_unsigned(
+			origin: OriginFor<T>,
+			payload: ClaimPayload<T>,
+			signer: T::AccountId,
+			signature: T::OffchainSignature,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+
+			Self::check_claim_signature(&payload, &signer, &signature, ClaimSubmission::Unsigned)?;
+			ensure!(!Self::is_unsigned_rate_limited(&signer), Error::<T>::UnsignedRateLimited);
+			let digest = payload.digest;
+			Self::do_create_signed_claim(&signer, payload)?;
+			LastUnsignedClaim::<T>::insert(&signer, frame_system::Pallet::<T>::block_number());
+
+			Self::deposit_event(Event::DigestClaimCreated(signer, digest));
+			Ok(().into())
+		}
+
+		/// Revoke any claim, returning its deposit to the owner.
+		///
+		/// The origin must be `ForceOrigin`.
+		#[pallet::call_index(16)]
+		#[pallet::weight(0)]
+		pub fn force_revoke_claim(
+			origin: OriginFor<T>,
+			digest: T::Hash,
+			reason: BoundedVec<u8, T::MaxReasonLength>,
+		) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			let info = Claims::<T>::get(digest).ok_or(Error::<T>::ClaimNotExist)?;
+			T::Currency::unreserve(&info.owner, Deposits::<T>::take(digest));
+			let block_number = frame_system::Pallet::<T>::block_number();
+			Self::remove_claim(&digest, &info, ProvenanceRecord::ForceRevoked { block_number });
+
+			Self::deposit_event(Event::ClaimForceRevoked(info.owner, digest, reason));
+			Ok(().into())
+		}
+
+		/// Transfer any claim to `dest`, along with its deposit.
+		///
+		/// The origin must be `ForceOrigin`.
+		#[pallet::call_index(17)]
+		#[pallet::weight(0)]
+		pub fn force_transfer_claim(
+			origin: OriginFor<T>,
+			dest: T::AccountId,
+			digest: T::Hash,
+			reason: BoundedVec<u8, T::MaxReasonLength>,
+		) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			let owner = Claims::<T>::get(digest).ok_or(Error::<T>::ClaimNotExist)?.owner;
+			Self::do_transfer_claim(&owner, &dest, digest)?;
+
+			Self::deposit_event(Event::ClaimForceTransferred(owner, dest, digest, reason));
+			Ok(().into())
+		}
+
+		/// Revoke an anchored Merkle root, returning its deposit.
+		#[pallet::call_index(18)]
+		#[pallet::weight(0)]
+		pub fn revoke_merkle_root(
+			origin: OriginFor<T>,
+			root: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let anchor = MerkleRoots::<T>::get(root).ok_or(Error::<T>::RootNotAnchored)?;
+			ensure!(anchor.owner == sender, Error::<T>::NotRootOwner);
+			T::Currency::unreserve(&sender, anchor.deposit);
+			MerkleRoots::<T>::remove(root);
+
+			Self::deposit_event(Event::MerkleRootRevoked(sender, root));
+			Ok(().into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let (payload, signer, signature) = match call {
+				Call::create_claim_unsigned { payload, signer, signature } =>
+					(payload, signer, signature),
+				_ => return InvalidTransaction::Call.into(),
+			};
+
+			if !signature.verify(&payload.encode()[..], signer) ||
+				payload.genesis_hash != Self::genesis_hash() ||
+				payload.submission != ClaimSubmission::Unsigned
+			{
+				return InvalidTransaction::BadProof.into()
+			}
+			let nonce = Nonces::<T>::get(signer);
+			if payload.nonce < nonce {
+				return InvalidTransaction::Stale.into()
+			}
+			if payload.nonce > nonce {
+				return InvalidTransaction::Future.into()
+			}
+			if Self::is_unsigned_rate_limited(signer) {
+				return InvalidTransaction::Custom(UNSIGNED_RATE_LIMITED).into()
+			}
+			// Reject the claims dispatch would fail on, so they cannot fill the pool for free.
+			if Claims::<T>::contains_key(payload.digest) && !Self::is_expired(&payload.digest) {
+				return InvalidTransaction::Custom(UNSIGNED_CLAIM_EXISTS).into()
+			}
+			if OwnerClaimCount::<T>::get(signer) >= T::MaxClaimsPerOwner::get() {
+				return InvalidTransaction::Custom(UNSIGNED_TOO_MANY_CLAIMS).into()
+			}
+			let info = ClaimInfo {
+				owner: signer.clone(),
+				block_number: frame_system::Pallet::<T>::block_number(),
+				description: payload.description.clone(),
+				raw: None,
+			};
+			let deposit = Self::claim_deposit(&payload.digest, &info);
+			if !T::Currency::can_reserve(signer, deposit) {
+				return InvalidTransaction::Payment.into()
+			}
+
+			// Only one unsigned claim per account is kept in the pool at a time.
+			ValidTransaction::with_tag_prefix("PoeUnsignedClaim")
+				.priority(T::UnsignedPriority::get())
+				.and_provides(signer)
+				.longevity(T::UnsignedInterval::get().saturated_into::<u64>())
+				.propagate(true)
+				.build()
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The digest under which the claim with the given raw bytes is stored in `Claims`.
+		pub fn claim_digest(claim: &[u8]) -> T::Hash {
+			T::Hashing::hash(claim)
+		}
+
+		/// The deposit required to store the given claim: `ClaimDeposit` per byte of the raw
+		/// claim (or of the digest for digest claims) and of its description.
+		pub fn claim_deposit(digest: &T::Hash, info: &ClaimInfo<T>) -> BalanceOf<T> {
+			let claim_len = info.raw.as_ref().map_or(digest.as_ref().len(), |raw| raw.len());
+			let description_len = info.description.as_ref().map_or(0, |d| d.len());
+			T::ClaimDeposit::get().saturating_mul(((claim_len + description_len) as u32).into())
+		}
+
+		/// The deposit required to anchor a Merkle root: `ClaimDeposit` per byte of the root, as
+		/// for a digest claim.
+		pub fn anchor_deposit(root: &T::Hash) -> BalanceOf<T> {
+			T::ClaimDeposit::get().saturating_mul((root.as_ref().len() as u32).into())
+		}
+
+		fn do_create_claim(
+			who: &T::AccountId,
+			digest: T::Hash,
+			description: Option<BoundedVec<u8, T::MaxDescriptionLength>>,
+			raw: Option<BoundedVec<u8, T::MaxClaimLength>>,
+			expires_at: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			if Claims::<T>::contains_key(digest) {
+				// An expired claim may be registered again, possibly by a new owner.
+				ensure!(Self::is_expired(&digest), Error::<T>::ProofAlreadyExists);
+				Self::expire_claim(&digest);
+			}
+
+			let info = ClaimInfo {
+				owner: who.clone(),
+				block_number: frame_system::Pallet::<T>::block_number(),
+				description,
+				raw,
+			};
+
+			let deposit = Self::claim_deposit(&digest, &info);
+			T::Currency::reserve(who, deposit)?;
+			Deposits::<T>::insert(digest, deposit);
+
+			Self::index_claim(who, &digest)?;
+			if let Some(expires_at) = expires_at {
+				Self::schedule_expiry(&digest, expires_at)?;
+			}
+
+			// A digest registered again starts a new history.
+			History::<T>::remove(digest);
+			Self::record_provenance(
+				&digest,
+				ProvenanceRecord::Created { who: who.clone(), block_number: info.block_number },
+			);
+			Self::insert_claim(&digest, &info);
+			Ok(())
+		}
+
+		/// Verify that `signature` is `signer`'s signature of `payload` and that the payload was
+		/// signed for this chain, for `submission` and with `signer`'s next nonce.
+		pub(crate) fn check_claim_signature(
+			payload: &ClaimPayload<T>,
+			signer: &T::AccountId,
+			signature: &T::OffchainSignature,
+			submission: ClaimSubmission,
+		) -> DispatchResult {
+			ensure!(signature.verify(&payload.encode()[..], signer), Error::<T>::InvalidSignature);
+			ensure!(payload.genesis_hash == Self::genesis_hash(), Error::<T>::WrongGenesis);
+			ensure!(payload.submission == submission, Error::<T>::WrongSubmission);
+			ensure!(payload.nonce == Nonces::<T>::get(signer), Error::<T>::InvalidNonce);
+			Ok(())
+		}
+
+		/// The hash of the genesis block, which signed claim payloads commit to.
+		pub fn genesis_hash() -> T::Hash {
+			frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero())
+		}
+
+		/// Register the claim of a verified payload and consume the signer's nonce.
+		fn do_create_signed_claim(
+			signer: &T::AccountId,
+			payload: ClaimPayload<T>,
+		) -> DispatchResult {
+			Self::do_create_claim(signer, payload.digest, payload.description, None, None)?;
+			Nonces::<T>::mutate(signer, |nonce| *nonce = nonce.saturating_add(1));
+			Ok(())
+		}
+
+		fn do_revoke_claim(who: &T::AccountId, digest: T::Hash) -> DispatchResult {
+			let info = Claims::<T>::get(digest).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(info.owner == *who, Error::<T>::NotClaimOwner);
+
+			T::Currency::unreserve(who, Deposits::<T>::take(digest));
+			let block_number = frame_system::Pallet::<T>::block_number();
+			let record = ProvenanceRecord::Revoked { who: who.clone(), block_number };
+			Self::remove_claim(&digest, &info, record);
+			Ok(())
+		}
+
+		fn do_transfer_claim(
+			who: &T::AccountId,
+			dest: &T::AccountId,
+			digest: T::Hash,
+		) -> DispatchResult {
+			let mut info = Claims::<T>::get(digest).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(info.owner == *who, Error::<T>::NotClaimOwner);
+			ensure!(!Self::is_expired(&digest), Error::<T>::ClaimHasExpired);
+
+			// The deposit follows the claim, so the recipient pays for its storage from now on.
+			T::Currency::repatriate_reserved(
+				who,
+				dest,
+				Deposits::<T>::get(digest),
+				BalanceStatus::Reserved,
+			)?;
+
+			Self::unindex_claim(who, &digest);
+			Self::index_claim(dest, &digest)?;
+			Self::take_pending_transfer(&digest);
+			info.owner = dest.clone();
+			info.block_number = frame_system::Pallet::<T>::block_number();
+
+			Self::record_provenance(
+				&digest,
+				ProvenanceRecord::Transferred {
+					from: who.clone(),
+					to: dest.clone(),
+					block_number: info.block_number,
+				},
+			);
+			Self::insert_claim(&digest, &info);
+			Ok(())
+		}
+
+		/// Append a record to the history of a claim, dropping the oldest record after the
+		/// creation record if the history is full.
+		pub(crate) fn record_provenance(digest: &T::Hash, record: ProvenanceRecordOf<T>) {
+			History::<T>::mutate(digest, |history| {
+				if let Err(record) = history.try_push(record) {
+					if history.len() > 1 {
+						history.remove(1);
+						let _ = history.try_push(record);
+					}
+				}
+			});
+		}
+
+		/// Verify that `leaf` is the leaf at `index` of the tree with the anchored `root`.
+		///
+		/// Inner nodes are `T::Hashing::hash(left ++ right)`. The last node of a level with an
+		/// odd number of nodes is promoted to the next level unchanged, so `proof` holds one
+		/// sibling per level in which the node has one, from the leaves up.
+		pub fn verify_merkle_proof(
+			root: T::Hash,
+			leaf: T::Hash,
+			index: u32,
+			proof: Vec<T::Hash>,
+		) -> bool {
+			let leaf_count = match MerkleRoots::<T>::get(root) {
+				Some(anchor) => anchor.leaf_count,
+				None => return false,
+			};
+			if index >= leaf_count {
+				return false
+			}
+
+			let mut proof = proof.into_iter();
+			let mut node = leaf;
+			let mut index = index;
+			let mut width = leaf_count;
+			while width > 1 {
+				let is_promoted = index == width - 1 && width % 2 == 1;
+				if !is_promoted {
+					let sibling = match proof.next() {
+						Some(sibling) => sibling,
+						None => return false,
+					};
+					let (left, right) =
+						if index % 2 == 0 { (node, sibling) } else { (sibling, node) };
+					let mut pair = left.as_ref().to_vec();
+					pair.extend_from_slice(right.as_ref());
+					node = T::Hashing::hash(&pair);
+				}
+				index /= 2;
+				width = (width + 1) / 2;
+			}
+
+			proof.next().is_none() && node == root
+		}
+
+		/// The chain of custody of a claim, oldest record first.
+		pub fn claim_history(digest: T::Hash) -> Vec<ProvenanceRecordOf<T>> {
+			History::<T>::get(digest).into_inner()
+		}
+
+		/// Write a claim to `Claims`, keeping the raw `Proofs` entry in sync.
+		pub(crate) fn insert_claim(digest: &T::Hash, info: &ClaimInfo<T>) {
+			if let Some(raw) = &info.raw {
+				Proofs::<T>::insert(raw, (info.owner.clone(), info.block_number));
+			}
+			Claims::<T>::insert(digest, info);
+		}
+
+		/// Remove a claim from `Claims`, along with its raw `Proofs` entry, its expiry, any
+		/// proposed transfer and its endorsements, and close its history with `record`.
+		fn remove_claim(digest: &T::Hash, info: &ClaimInfo<T>, record: ProvenanceRecordOf<T>) {
+			if let Some(raw) = &info.raw {
+				Proofs::<T>::remove(raw);
+			}
+			Self::record_provenance(digest, record);
+			Self::unschedule_expiry(digest);
+			Self::take_pending_transfer(digest);
+			CoSignedClaims::<T>::remove(digest);
+			Self::unindex_claim(&info.owner, digest);
+			Claims::<T>::remove(digest);
+		}
+
+		/// Remove the proposed transfer of a claim, if any, along with its `TransferExpiries`
+		/// entry.
+		fn take_pending_transfer(digest: &T::Hash) -> Option<PendingTransfer<T>> {
+			let pending = PendingTransfers::<T>::take(digest)?;
+			TransferExpiries::<T>::remove(pending.expires_at, digest);
+			Some(pending)
+		}
+
+		/// Add a claim to the `ClaimsByOwner` index of `owner`.
+		pub(crate) fn index_claim(owner: &T::AccountId, digest: &T::Hash) -> DispatchResult {
+			OwnerClaimCount::<T>::try_mutate(owner, |count| -> DispatchResult {
+				ensure!(*count < T::MaxClaimsPerOwner::get(), Error::<T>::TooManyClaims);
+				*count += 1;
+				Ok(())
+			})?;
+			ClaimsByOwner::<T>::insert(owner, digest, ());
+			Ok(())
+		}
+
+		fn unindex_claim(owner: &T::AccountId, digest: &T::Hash) {
+			if ClaimsByOwner::<T>::take(owner, digest).is_some() {
+				OwnerClaimCount::<T>::mutate_exists(owner, |count| {
+					*count = count.and_then(|count| count.checked_sub(1)).filter(|c| *c > 0);
+				});
+			}
+		}
+
+		/// The digests of all claims owned by `owner`.
+		pub fn claims_of(owner: T::AccountId) -> Vec<T::Hash> {
+			ClaimsByOwner::<T>::iter_key_prefix(owner).collect()
+		}
+
+		/// The endorsement status of a co-signed claim, or `None` for other claims.
+		pub fn endorsement_status(
+			digest: T::Hash,
+		) -> Option<EndorsementStatus<T::AccountId, T::BlockNumber>> {
+			CoSignedClaims::<T>::get(digest).map(|co_signers| EndorsementStatus {
+				finalized: co_signers.endorsed.len() == co_signers.required.len(),
+				required: co_signers.required.into_inner(),
+				endorsed: co_signers.endorsed.into_inner(),
+				deadline: co_signers.deadline,
+			})
+		}
+
+		/// Whether `who` registered an unsigned claim less than `UnsignedInterval` blocks ago.
+		fn is_unsigned_rate_limited(who: &T::AccountId) -> bool {
+			LastUnsignedClaim::<T>::get(who).map_or(false, |last| {
+				frame_system::Pallet::<T>::block_number() <
+					last.saturating_add(T::UnsignedInterval::get())
+			})
+		}
+
+		/// Whether the claim has reached its expiry block.
+		pub fn is_expired(digest: &T::Hash) -> bool {
+			ClaimExpiry::<T>::get(digest)
+				.map_or(false, |expires_at| expires_at <= frame_system::Pallet::<T>::block_number())
+		}
+
+		fn schedule_expiry(digest: &T::Hash, expires_at: T::BlockNumber) -> DispatchResult {
+			ensure!(
+				expires_at > frame_system::Pallet::<T>::block_number(),
+				Error::<T>::InvalidExpiry
+			);
+
+			ExpiryQueue::<T>::try_mutate(expires_at, |queue| queue.try_push(*digest))
+				.map_err(|_| Error::<T>::TooManyExpiries)?;
+			ClaimExpiry::<T>::insert(digest, expires_at);
+			Ok(())
+		}
+
+		fn unschedule_expiry(digest: &T::Hash) {
+			if let Some(expires_at) = ClaimExpiry::<T>::take(digest) {
+				ExpiryQueue::<T>::mutate_exists(expires_at, |queue| {
+					let is_empty = queue.as_mut().map_or(true, |queue| {
+						queue.retain(|d| d != digest);
+						queue.is_empty()
+					});
+					if is_empty {
+						*queue = None;
+					}
+				});
+			}
+		}
+
+		/// Remove an expired claim, returning its deposit to the owner.
+		fn expire_claim(digest: &T::Hash) {
+			if let Some(info) = Claims::<T>::get(digest) {
+				T::Currency::unreserve(&info.owner, Deposits::<T>::take(digest));
+				let block_number = frame_system::Pallet::<T>::block_number();
+				Self::remove_claim(digest, &info, ProvenanceRecord::Expired { block_number });
+				Self::deposit_event(Event::ClaimExpired(info.owner, *digest));
+			}
+		}
+
+		/// Drain `ExpiryQueue` up to and including `now`, using at most `limit` weight.
+		pub(crate) fn expire_claims(now: T::BlockNumber, limit: Weight) -> Weight {
+			let db_weight = T::DbWeight::get();
+			// The cursor is read and written once.
+			let mut used = db_weight.reads_writes(1, 1);
+			if used.any_gt(limit) {
+				return Weight::zero()
+			}
+
+			let bucket_weight = db_weight.reads_writes(1, 1);
+			// `expire_claim` reads the claim and its deposit, unreserves it, appends to the
+			// history and removes `Proofs`, `Claims`, `ClaimExpiry`, `Deposits`,
+			// `PendingTransfers` and `CoSignedClaims`.
+			let claim_weight = db_weight.reads_writes(5, 9);
+
+			let mut cursor = ExpiryCursor::<T>::get();
+			while cursor <= now {
+				if used.saturating_add(bucket_weight).any_gt(limit) {
+					break
+				}
+				used = used.saturating_add(bucket_weight);
+
+				let mut bucket = ExpiryQueue::<T>::take(cursor).into_inner();
+				while let Some(digest) = bucket.last().copied() {
+					if used.saturating_add(claim_weight).any_gt(limit) {
+						break
+					}
+					used = used.saturating_add(claim_weight);
+
+					bucket.pop();
+					if Self::is_expired(&digest) {
+						Self::expire_claim(&digest);
+					}
+				}
+
+				if !bucket.is_empty() {
+					ExpiryQueue::<T>::insert(cursor, BoundedVec::truncate_from(bucket));
+					break
+				}
+				cursor = cursor.saturating_add(One::one());
+			}
+			ExpiryCursor::<T>::put(cursor);
+
+			used
+		}
+
+		/// Drop the transfer proposals expiring up to and including `now`, using at most
+		/// `limit` weight.
+		pub(crate) fn expire_transfer_proposals(now: T::BlockNumber, limit: Weight) -> Weight {
+			let db_weight = T::DbWeight::get();
+			// The cursor is read and written once.
+			let mut used = db_weight.reads_writes(1, 1);
+			if used.any_gt(limit) {
+				return Weight::zero()
+			}
+
+			// Looking up the next proposal expiring in a block, or finding there is none.
+			let lookup_weight = db_weight.reads(1);
+			// Removing the proposal and its `TransferExpiries` entry.
+			let proposal_weight = db_weight.writes(2);
+			let step_weight = lookup_weight.saturating_add(proposal_weight);
+
+			let mut cursor = TransferExpiryCursor::<T>::get();
+			while cursor <= now {
+				if used.saturating_add(step_weight).any_gt(limit) {
+					break
+				}
+				used = used.saturating_add(lookup_weight);
+
+				match TransferExpiries::<T>::iter_key_prefix(cursor).next() {
+					Some(digest) => {
+						used = used.saturating_add(proposal_weight);
+						TransferExpiries::<T>::remove(cursor, digest);
+						PendingTransfers::<T>::remove(digest);
+					},
+					None => cursor = cursor.saturating_add(One::one()),
+				}
+			}
+			TransferExpiryCursor::<T>::put(cursor);
+
+			used
+		}
 	}
 }
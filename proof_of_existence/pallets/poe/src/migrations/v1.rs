@@ -0,0 +1,25 @@
+use crate::{ClaimInfo, Claims, Config, Pallet, Proofs};
+use frame_support::{pallet_prelude::*, traits::GetStorageVersion, weights::Weight};
+
+/// Register every raw claim in `Proofs` under its digest in `Claims`.
+pub fn migrate<T: Config>() -> Weight {
+	let on_chain_version = Pallet::<T>::on_chain_storage_version();
+
+	if on_chain_version != 0 {
+		return Weight::zero()
+	}
+
+	let mut count = 0u64;
+	for (claim, (owner, block_number)) in Proofs::<T>::iter() {
+		let digest = Pallet::<T>::claim_digest(&claim);
+		Claims::<T>::insert(
+			digest,
+			ClaimInfo { owner, block_number, description: None, raw: Some(claim) },
+		);
+		count += 1;
+	}
+
+	StorageVersion::new(1).put::<Pallet<T>>();
+
+	T::DbWeight::get().reads_writes(count + 1, count + 1)
+}
@@ -0,0 +1,25 @@
+use crate::{Claims, ClaimsByOwner, Config, OwnerClaimCount, Pallet};
+use frame_support::{pallet_prelude::*, traits::GetStorageVersion, weights::Weight};
+
+/// Build the `ClaimsByOwner` index from the existing claims.
+///
+/// Existing claims are indexed even if an account ends up owning more than
+/// `MaxClaimsPerOwner` of them; such an account cannot receive new claims until it owns fewer.
+pub fn migrate<T: Config>() -> Weight {
+	let on_chain_version = Pallet::<T>::on_chain_storage_version();
+
+	if on_chain_version != 1 {
+		return Weight::zero()
+	}
+
+	let mut count = 0u64;
+	for (digest, info) in Claims::<T>::iter() {
+		ClaimsByOwner::<T>::insert(&info.owner, digest, ());
+		OwnerClaimCount::<T>::mutate(&info.owner, |count| *count = count.saturating_add(1));
+		count += 1;
+	}
+
+	StorageVersion::new(2).put::<Pallet<T>>();
+
+	T::DbWeight::get().reads_writes(2 * count + 1, 2 * count + 1)
+}
@@ -0,0 +1,55 @@
+use crate::{
+	Claims, Config, ExpiryCursor, History, Pallet, PendingTransfer, PendingTransfers,
+	ProvenanceRecord, TransferExpiries, TransferExpiryCursor,
+};
+use frame_support::{pallet_prelude::*, traits::GetStorageVersion, weights::Weight};
+use sp_std::prelude::*;
+
+/// Seed the history of the claims registered before provenance was recorded. Start
+/// `ExpiryCursor` at the current block if expiries have never been drained, so `on_idle` does not
+/// walk `ExpiryQueue` from genesis.
+///
+/// Transfer proposals that have already expired are dropped, and the others are scheduled in
+/// `TransferExpiries` from the current block on.
+///
+/// Runtimes before this version kept no history, so each claim is given a `Created` record with
+/// its current owner and the block of its last transfer, the oldest custody data left.
+pub fn migrate<T: Config>() -> Weight {
+	let on_chain_version = Pallet::<T>::on_chain_storage_version();
+
+	if on_chain_version != 2 {
+		return Weight::zero()
+	}
+
+	let mut claims = 0u64;
+	let mut seeded = 0u64;
+	for (digest, info) in Claims::<T>::iter() {
+		claims += 1;
+		if !History::<T>::contains_key(digest) {
+			let record =
+				ProvenanceRecord::Created { who: info.owner, block_number: info.block_number };
+			History::<T>::insert(digest, BoundedVec::truncate_from(vec![record]));
+			seeded += 1;
+		}
+	}
+
+	let now = frame_system::Pallet::<T>::block_number();
+	if !ExpiryCursor::<T>::exists() {
+		ExpiryCursor::<T>::put(now);
+	}
+
+	let mut proposals = 0u64;
+	PendingTransfers::<T>::translate::<PendingTransfer<T>, _>(|digest, pending| {
+		proposals += 1;
+		if pending.expires_at <= now {
+			return None
+		}
+		TransferExpiries::<T>::insert(pending.expires_at, digest, ());
+		Some(pending)
+	});
+	TransferExpiryCursor::<T>::put(now);
+
+	StorageVersion::new(3).put::<Pallet<T>>();
+
+	T::DbWeight::get().reads_writes(2 * claims + proposals + 3, seeded + 2 * proposals + 3)
+}
@@ -1,13 +1,18 @@
 use crate as pallet_poe;
-use frame_support::traits::{ConstU16, ConstU32, ConstU64};
+use frame_support::{
+	parameter_types,
+	traits::{ConstU128, ConstU16, ConstU32, ConstU64, GenesisBuild},
+	weights::constants::RocksDbWeight,
+};
 use sp_core::H256;
 use sp_runtime::{
-	testing::Header,
+	testing::{Header, TestSignature, UintAuthorityId},
 	traits::{BlakeTwo256, IdentityLookup},
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
+pub type Balance = u128;
 
 // Configure a mock runtime to test the pallet.
 frame_support::construct_runtime!(
@@ -17,6 +22,7 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system,
+		Balances: pallet_balances,
 		PoeModule: pallet_poe,
 	}
 );
@@ -25,7 +31,7 @@ impl frame_system::Config for Test {
 	type BaseCallFilter = frame_support::traits::Everything;
 	type BlockWeights = ();
 	type BlockLength = ();
-	type DbWeight = ();
+	type DbWeight = RocksDbWeight;
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeCall = RuntimeCall;
 	type Index = u64;
@@ -39,7 +45,7 @@ impl frame_system::Config for Test {
 	type BlockHashCount = ConstU64<250>;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<Balance>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
@@ -48,12 +54,72 @@ impl frame_system::Config for Test {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+impl pallet_balances::Config for Test {
+	type MaxLocks = ConstU32<50>;
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = Balance;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU128<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type FreezeIdentifier = ();
+	type MaxFreezes = ();
+	type HoldIdentifier = ();
+	type MaxHolds = ();
+}
+
+/// Deposit reserved per byte of a claim.
+pub const CLAIM_DEPOSIT: Balance = 10;
+
+/// Free balance of each of the accounts endowed at genesis.
+pub const INITIAL_BALANCE: Balance = 1_000;
+
+parameter_types! {
+	pub static AllowDirectTransfer: bool = true;
+}
+
 impl pallet_poe::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type MaxClaimLength = ConstU32<10>;
+	type MaxDescriptionLength = ConstU32<16>;
+	type Currency = Balances;
+	type ClaimDeposit = ConstU128<CLAIM_DEPOSIT>;
+	type MaxHistoryLength = ConstU32<3>;
+	type MaxExpiriesPerBlock = ConstU32<3>;
+	type MaxBatchSize = ConstU32<4>;
+	type MaxClaimsPerOwner = ConstU32<3>;
+	type AllowDirectTransfer = AllowDirectTransfer;
+	type TransferProposalPeriod = ConstU64<10>;
+	type MaxSigners = ConstU32<3>;
+	type OffchainSignature = TestSignature;
+	type OffchainPublic = UintAuthorityId;
+	type UnsignedPriority = ConstU64<100>;
+	type UnsignedInterval = ConstU64<5>;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type MaxReasonLength = ConstU32<16>;
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	new_test_ext_with_claims(vec![])
+}
+
+// Build genesis storage with the given claims pre-registered.
+pub fn new_test_ext_with_claims(claims: Vec<(Vec<u8>, u64)>) -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, INITIAL_BALANCE), (2, INITIAL_BALANCE), (3, INITIAL_BALANCE)],
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+	pallet_poe::GenesisConfig::<Test> { claims }
+		.assimilate_storage(&mut storage)
+		.unwrap();
+
+	let mut ext: sp_io::TestExternalities = storage.into();
+	// Events are not deposited in the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
 }
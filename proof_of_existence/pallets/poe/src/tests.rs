@@ -1,6 +1,16 @@
 use super::*;
 use crate::{mock::*, Error, Event};
-use frame_support::{assert_noop, assert_ok, BoundedVec};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::GetDispatchInfo,
+	pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned},
+	traits::{Get, GetStorageVersion, Hooks, StorageVersion},
+	weights::Weight,
+	BoundedVec,
+};
+use sp_core::H256;
+use sp_runtime::{testing::TestSignature, traits::Hash};
 
 #[test]
 fn create_claim_works() {
@@ -89,4 +99,1188 @@ fn transfer_claim_failed_when_claim_not_exist() {
 			Error::<Test>::ClaimNotExist
 		);
 	});
-}
\ No newline at end of file
+}
+
+#[test]
+fn genesis_config_registers_claims() {
+	new_test_ext_with_claims(vec![(vec![0, 1, 2, 3], 1), (vec![4, 5], 2)]).execute_with(|| {
+		let claim = BoundedVec::try_from(vec![0, 1, 2, 3]).unwrap();
+		assert_eq!(Proofs::<Test>::get(&claim), Some((1, 0)));
+
+		let claim = BoundedVec::try_from(vec![4, 5]).unwrap();
+		assert_eq!(Proofs::<Test>::get(&claim), Some((2, 0)));
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(2), claim));
+	});
+}
+
+#[test]
+#[should_panic(expected = "genesis claim is longer than MaxClaimLength")]
+fn genesis_config_rejects_too_long_claim() {
+	new_test_ext_with_claims(vec![(vec![0; 11], 1)]);
+}
+
+#[test]
+#[should_panic(expected = "duplicate claim in genesis")]
+fn genesis_config_rejects_duplicate_claim() {
+	new_test_ext_with_claims(vec![(vec![0, 1], 1), (vec![0, 1], 2)]);
+}
+
+#[test]
+fn create_claim_registers_digest() {
+	new_test_ext().execute_with(|| {
+		let claim: BoundedVec<u8, _> = BoundedVec::try_from(vec![0, 1, 2, 3]).unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+
+		let info = Claims::<Test>::get(PoeModule::claim_digest(&claim)).unwrap();
+		assert_eq!(info.owner, 1);
+		assert_eq!(info.raw, Some(claim));
+	});
+}
+
+#[test]
+fn create_digest_claim_works() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		let description = BoundedVec::try_from(b"contract".to_vec()).unwrap();
+		assert_ok!(PoeModule::create_digest_claim(
+			RuntimeOrigin::signed(1),
+			digest,
+			Some(description.clone()),
+			None
+		));
+
+		assert_eq!(
+			Claims::<Test>::get(digest),
+			Some(ClaimInfo {
+				owner: 1,
+				block_number: frame_system::Pallet::<Test>::block_number(),
+				description: Some(description),
+				raw: None,
+			})
+		);
+		System::assert_last_event(Event::DigestClaimCreated(1, digest).into());
+
+		assert_noop!(
+			PoeModule::create_digest_claim(RuntimeOrigin::signed(2), digest, None, None),
+			Error::<Test>::ProofAlreadyExists
+		);
+	});
+}
+
+#[test]
+fn create_digest_claim_failed_when_raw_claim_exists() {
+	new_test_ext().execute_with(|| {
+		let claim = BoundedVec::try_from(vec![0, 1, 2, 3]).unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_noop!(
+			PoeModule::create_digest_claim(
+				RuntimeOrigin::signed(2),
+				PoeModule::claim_digest(&claim),
+				None,
+				None
+			),
+			Error::<Test>::ProofAlreadyExists
+		);
+	});
+}
+
+#[test]
+fn revoke_digest_claim_works() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		assert_noop!(
+			PoeModule::revoke_digest_claim(RuntimeOrigin::signed(1), digest),
+			Error::<Test>::ClaimNotExist
+		);
+
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), digest, None, None));
+		assert_noop!(
+			PoeModule::revoke_digest_claim(RuntimeOrigin::signed(2), digest),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::revoke_digest_claim(RuntimeOrigin::signed(1), digest));
+		assert_eq!(Claims::<Test>::get(digest), None);
+	});
+}
+
+#[test]
+fn revoke_digest_claim_removes_raw_proof() {
+	new_test_ext().execute_with(|| {
+		let claim = BoundedVec::try_from(vec![0, 1, 2, 3]).unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::revoke_digest_claim(
+			RuntimeOrigin::signed(1),
+			PoeModule::claim_digest(&claim)
+		));
+		assert_eq!(Proofs::<Test>::get(&claim), None);
+	});
+}
+
+#[test]
+fn transfer_digest_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = BoundedVec::try_from(vec![0, 1, 2, 3]).unwrap();
+		let digest = PoeModule::claim_digest(&claim);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+
+		assert_noop!(
+			PoeModule::transfer_digest_claim(RuntimeOrigin::signed(2), 3, digest),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::transfer_digest_claim(RuntimeOrigin::signed(1), 2, digest));
+
+		assert_eq!(Claims::<Test>::get(digest).unwrap().owner, 2);
+		assert_eq!(Proofs::<Test>::get(&claim).unwrap().0, 2);
+	});
+}
+
+#[test]
+fn migrate_to_v1_registers_raw_claims() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<PoeModule>();
+		let claim: BoundedVec<u8, _> = BoundedVec::try_from(vec![0, 1, 2, 3]).unwrap();
+		Proofs::<Test>::insert(&claim, (1, 5));
+
+		crate::migrations::v1::migrate::<Test>();
+
+		assert_eq!(PoeModule::on_chain_storage_version(), 1);
+		assert_eq!(
+			Claims::<Test>::get(PoeModule::claim_digest(&claim)),
+			Some(ClaimInfo { owner: 1, block_number: 5, description: None, raw: Some(claim) })
+		);
+	});
+}
+
+#[test]
+fn create_claim_reserves_deposit() {
+	new_test_ext().execute_with(|| {
+		let claim = BoundedVec::try_from(vec![0, 1, 2, 3]).unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+
+		let deposit = 4 * CLAIM_DEPOSIT;
+		assert_eq!(Deposits::<Test>::get(PoeModule::claim_digest(&claim)), deposit);
+		assert_eq!(Balances::reserved_balance(1), deposit);
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE - deposit);
+	});
+}
+
+#[test]
+fn create_digest_claim_deposit_covers_description() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		let description = BoundedVec::try_from(b"contract".to_vec()).unwrap();
+		assert_ok!(PoeModule::create_digest_claim(
+			RuntimeOrigin::signed(1),
+			digest,
+			Some(description),
+			None
+		));
+
+		assert_eq!(Balances::reserved_balance(1), (32 + 8) * CLAIM_DEPOSIT);
+	});
+}
+
+#[test]
+fn create_claim_failed_when_deposit_cannot_be_reserved() {
+	new_test_ext().execute_with(|| {
+		let claim = BoundedVec::try_from(vec![0, 1, 2, 3]).unwrap();
+		assert_noop!(
+			PoeModule::create_claim(RuntimeOrigin::signed(4), claim),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn transfer_claim_moves_deposit() {
+	new_test_ext().execute_with(|| {
+		let claim = BoundedVec::try_from(vec![0, 1, 2, 3]).unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::transfer_claim(RuntimeOrigin::signed(1), 2, claim.clone()));
+
+		let deposit = 4 * CLAIM_DEPOSIT;
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE - deposit);
+		assert_eq!(Balances::reserved_balance(2), deposit);
+		assert_eq!(Balances::free_balance(2), INITIAL_BALANCE);
+
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(2), claim));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), INITIAL_BALANCE + deposit);
+	});
+}
+
+#[test]
+fn revoke_claim_unreserves_deposit() {
+	new_test_ext().execute_with(|| {
+		let claim = BoundedVec::try_from(vec![0, 1, 2, 3]).unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(1), claim.clone()));
+
+		assert_eq!(Deposits::<Test>::get(PoeModule::claim_digest(&claim)), 0);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn claim_history_records_chain_of_custody() {
+	new_test_ext().execute_with(|| {
+		let claim = BoundedVec::try_from(vec![0, 1, 2, 3]).unwrap();
+		let digest = PoeModule::claim_digest(&claim);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+
+		System::set_block_number(2);
+		assert_ok!(PoeModule::transfer_claim(RuntimeOrigin::signed(1), 2, claim.clone()));
+		System::assert_last_event(Event::ClaimTransfered(1, 2, claim.clone()).into());
+
+		assert_eq!(
+			PoeModule::claim_history(digest),
+			vec![
+				ProvenanceRecord::Created { who: 1, block_number: 1 },
+				ProvenanceRecord::Transferred { from: 1, to: 2, block_number: 2 },
+			]
+		);
+
+		// The history outlives the claim and records its revocation.
+		System::set_block_number(3);
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(2), claim.clone()));
+		assert_eq!(
+			PoeModule::claim_history(digest),
+			vec![
+				ProvenanceRecord::Created { who: 1, block_number: 1 },
+				ProvenanceRecord::Transferred { from: 1, to: 2, block_number: 2 },
+				ProvenanceRecord::Revoked { who: 2, block_number: 3 },
+			]
+		);
+
+		// Registering the digest again starts a new history.
+		System::set_block_number(4);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim));
+		assert_eq!(
+			PoeModule::claim_history(digest),
+			vec![ProvenanceRecord::Created { who: 1, block_number: 4 }]
+		);
+	});
+}
+
+#[test]
+fn claim_history_keeps_creation_record_when_full() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), digest, None, None));
+		assert_ok!(PoeModule::transfer_digest_claim(RuntimeOrigin::signed(1), 2, digest));
+		assert_ok!(PoeModule::transfer_digest_claim(RuntimeOrigin::signed(2), 3, digest));
+		assert_ok!(PoeModule::transfer_digest_claim(RuntimeOrigin::signed(3), 1, digest));
+		System::assert_last_event(Event::DigestClaimTransfered(3, 1, digest).into());
+
+		assert_eq!(
+			PoeModule::claim_history(digest),
+			vec![
+				ProvenanceRecord::Created { who: 1, block_number: 1 },
+				ProvenanceRecord::Transferred { from: 2, to: 3, block_number: 1 },
+				ProvenanceRecord::Transferred { from: 3, to: 1, block_number: 1 },
+			]
+		);
+	});
+}
+
+#[test]
+fn claim_expires_in_on_idle() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		assert_noop!(
+			PoeModule::create_digest_claim(RuntimeOrigin::signed(1), digest, None, Some(1)),
+			Error::<Test>::InvalidExpiry
+		);
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), digest, None, Some(3)));
+		assert_eq!(PoeModule::claim_expiry(digest), Some(3));
+
+		System::set_block_number(2);
+		PoeModule::on_idle(2, Weight::MAX);
+		assert!(Claims::<Test>::contains_key(digest));
+
+		System::set_block_number(3);
+		PoeModule::on_idle(3, Weight::MAX);
+		assert!(!Claims::<Test>::contains_key(digest));
+		assert_eq!(PoeModule::claim_expiry(digest), None);
+		assert_eq!(ExpiryQueue::<Test>::get(3).len(), 0);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		System::assert_last_event(Event::ClaimExpired(1, digest).into());
+		assert_eq!(
+			PoeModule::claim_history(digest),
+			vec![
+				ProvenanceRecord::Created { who: 1, block_number: 1 },
+				ProvenanceRecord::Expired { block_number: 3 },
+			]
+		);
+	});
+}
+
+#[test]
+fn expire_claims_respects_weight_limit() {
+	new_test_ext().execute_with(|| {
+		for i in 1..=3 {
+			assert_ok!(PoeModule::create_digest_claim(
+				RuntimeOrigin::signed(1),
+				H256::repeat_byte(i),
+				None,
+				Some(2)
+			));
+		}
+		// Move the cursor past the empty blocks.
+		PoeModule::on_idle(1, Weight::MAX);
+
+		System::set_block_number(2);
+		assert_eq!(PoeModule::on_idle(2, Weight::zero()), Weight::zero());
+		assert_eq!(ExpiryQueue::<Test>::get(2).len(), 3);
+
+		// Enough weight for the cursor, one block and a single claim.
+		let db_weight = <Test as frame_system::Config>::DbWeight::get();
+		let limit = db_weight.reads_writes(2, 2).saturating_add(db_weight.reads_writes(5, 9));
+		assert_eq!(PoeModule::on_idle(2, limit), limit);
+		assert_eq!(ExpiryQueue::<Test>::get(2).len(), 2);
+		assert_eq!(Claims::<Test>::iter().count(), 2);
+
+		PoeModule::on_idle(2, Weight::MAX);
+		assert_eq!(ExpiryQueue::<Test>::get(2).len(), 0);
+		assert_eq!(Claims::<Test>::iter().count(), 0);
+		assert_eq!(ExpiryCursor::<Test>::get(), 3);
+	});
+}
+
+#[test]
+fn expired_claim_can_be_registered_by_new_owner() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), digest, None, Some(2)));
+		assert_noop!(
+			PoeModule::create_digest_claim(RuntimeOrigin::signed(2), digest, None, None),
+			Error::<Test>::ProofAlreadyExists
+		);
+
+		// The claim expired but `on_idle` has not cleaned it up yet.
+		System::set_block_number(2);
+		assert_noop!(
+			PoeModule::transfer_digest_claim(RuntimeOrigin::signed(1), 3, digest),
+			Error::<Test>::ClaimHasExpired
+		);
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(2), digest, None, None));
+
+		assert_eq!(PoeModule::claims(digest).unwrap().owner, 2);
+		assert_eq!(PoeModule::claim_expiry(digest), None);
+		assert_eq!(ExpiryQueue::<Test>::get(2).len(), 0);
+		assert_eq!(Balances::reserved_balance(1), 0);
+
+		// The stale queue entry is gone, so the new claim survives `on_idle`.
+		PoeModule::on_idle(2, Weight::MAX);
+		assert!(Claims::<Test>::contains_key(digest));
+	});
+}
+
+#[test]
+fn renew_claim_works() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), digest, None, Some(3)));
+
+		assert_noop!(
+			PoeModule::renew_claim(RuntimeOrigin::signed(2), digest, Some(10)),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::renew_claim(RuntimeOrigin::signed(1), digest, Some(10)));
+		System::assert_last_event(Event::ClaimRenewed(1, digest, Some(10)).into());
+		assert_eq!(PoeModule::claim_expiry(digest), Some(10));
+		assert_eq!(ExpiryQueue::<Test>::get(3).len(), 0);
+		assert_eq!(ExpiryQueue::<Test>::get(10).len(), 1);
+
+		System::set_block_number(3);
+		PoeModule::on_idle(3, Weight::MAX);
+		assert!(Claims::<Test>::contains_key(digest));
+
+		assert_ok!(PoeModule::renew_claim(RuntimeOrigin::signed(1), digest, None));
+		assert_eq!(PoeModule::claim_expiry(digest), None);
+		assert_eq!(ExpiryQueue::<Test>::get(10).len(), 0);
+	});
+}
+
+#[test]
+fn create_claims_batch_works() {
+	new_test_ext().execute_with(|| {
+		let description = BoundedVec::try_from(b"invoice".to_vec()).unwrap();
+		let claims = BoundedVec::try_from(vec![
+			(H256::repeat_byte(1), None),
+			(H256::repeat_byte(2), Some(description)),
+		])
+		.unwrap();
+		assert_ok!(PoeModule::create_claims_batch(RuntimeOrigin::signed(1), claims));
+
+		assert_eq!(PoeModule::claims(H256::repeat_byte(1)).unwrap().owner, 1);
+		assert_eq!(PoeModule::claims(H256::repeat_byte(2)).unwrap().owner, 1);
+		System::assert_last_event(Event::DigestClaimCreated(1, H256::repeat_byte(2)).into());
+	});
+}
+
+#[test]
+fn create_claims_batch_is_all_or_nothing() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(PoeModule::create_digest_claim(
+			RuntimeOrigin::signed(2),
+			H256::repeat_byte(2),
+			None,
+			None
+		));
+
+		let claims =
+			BoundedVec::try_from(vec![(H256::repeat_byte(1), None), (H256::repeat_byte(2), None)])
+				.unwrap();
+		assert_noop!(
+			PoeModule::create_claims_batch(RuntimeOrigin::signed(1), claims),
+			Error::<Test>::ProofAlreadyExists
+		);
+		assert!(!Claims::<Test>::contains_key(H256::repeat_byte(1)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
+
+#[test]
+fn create_claims_batch_weight_scales_with_its_size() {
+	new_test_ext().execute_with(|| {
+		let max_batch_size = <Test as Config>::MaxBatchSize::get();
+		let batch = |size: u32| {
+			let claims: Vec<_> = (0..size).map(|i| (H256::repeat_byte(i as u8), None)).collect();
+			crate::Call::<Test>::create_claims_batch {
+				claims: BoundedVec::try_from(claims).unwrap(),
+			}
+		};
+		let claim_weight = <Test as frame_system::Config>::DbWeight::get().reads_writes(14, 19);
+
+		assert_eq!(batch(1).get_dispatch_info().weight, claim_weight);
+		assert_eq!(
+			batch(max_batch_size).get_dispatch_info().weight,
+			claim_weight.saturating_mul(max_batch_size as u64)
+		);
+		let too_many: Vec<(H256, Option<BoundedVec<u8, <Test as Config>::MaxDescriptionLength>>)> =
+			(0..=max_batch_size).map(|i| (H256::repeat_byte(i as u8), None)).collect();
+		assert!(BoundedVec::<_, <Test as Config>::MaxBatchSize>::try_from(too_many).is_err());
+	});
+}
+
+fn hash_pair(left: H256, right: H256) -> H256 {
+	<Test as frame_system::Config>::Hashing::hash(&[left.as_bytes(), right.as_bytes()].concat())
+}
+
+#[test]
+fn anchor_merkle_root_works() {
+	new_test_ext().execute_with(|| {
+		let root = H256::repeat_byte(1);
+		assert_noop!(
+			PoeModule::anchor_merkle_root(RuntimeOrigin::signed(1), root, 0),
+			Error::<Test>::EmptyMerkleTree
+		);
+		assert_noop!(
+			PoeModule::anchor_merkle_root(RuntimeOrigin::signed(4), root, 3),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+		assert_ok!(PoeModule::anchor_merkle_root(RuntimeOrigin::signed(1), root, 3));
+		System::assert_last_event(Event::MerkleRootAnchored(1, root, 3).into());
+		let deposit = 32 * CLAIM_DEPOSIT;
+		assert_eq!(
+			PoeModule::merkle_roots(root),
+			Some(MerkleAnchor { owner: 1, block_number: 1, leaf_count: 3, deposit })
+		);
+		assert_eq!(Balances::reserved_balance(1), deposit);
+		assert_noop!(
+			PoeModule::anchor_merkle_root(RuntimeOrigin::signed(2), root, 3),
+			Error::<Test>::RootAlreadyAnchored
+		);
+	});
+}
+
+#[test]
+fn revoke_merkle_root_works() {
+	new_test_ext().execute_with(|| {
+		let root = H256::repeat_byte(1);
+		assert_noop!(
+			PoeModule::revoke_merkle_root(RuntimeOrigin::signed(1), root),
+			Error::<Test>::RootNotAnchored
+		);
+		assert_ok!(PoeModule::anchor_merkle_root(RuntimeOrigin::signed(1), root, 3));
+		assert_noop!(
+			PoeModule::revoke_merkle_root(RuntimeOrigin::signed(2), root),
+			Error::<Test>::NotRootOwner
+		);
+
+		assert_ok!(PoeModule::revoke_merkle_root(RuntimeOrigin::signed(1), root));
+		System::assert_last_event(Event::MerkleRootRevoked(1, root).into());
+		assert_eq!(PoeModule::merkle_roots(root), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(!PoeModule::verify_merkle_proof(root, root, 0, vec![]));
+
+		// A revoked root can be anchored again.
+		assert_ok!(PoeModule::anchor_merkle_root(RuntimeOrigin::signed(2), root, 1));
+	});
+}
+
+#[test]
+fn verify_merkle_proof_works() {
+	new_test_ext().execute_with(|| {
+		let (a, b, c) = (H256::repeat_byte(1), H256::repeat_byte(2), H256::repeat_byte(3));
+		let ab = hash_pair(a, b);
+		let root = hash_pair(ab, c);
+
+		// Nothing verifies against a root that was not anchored.
+		assert!(!PoeModule::verify_merkle_proof(root, a, 0, vec![b, c]));
+
+		assert_ok!(PoeModule::anchor_merkle_root(RuntimeOrigin::signed(1), root, 3));
+		assert!(PoeModule::verify_merkle_proof(root, a, 0, vec![b, c]));
+		assert!(PoeModule::verify_merkle_proof(root, b, 1, vec![a, c]));
+		// `c` is promoted past the first level.
+		assert!(PoeModule::verify_merkle_proof(root, c, 2, vec![ab]));
+
+		assert!(!PoeModule::verify_merkle_proof(root, a, 1, vec![b, c]));
+		assert!(!PoeModule::verify_merkle_proof(root, c, 3, vec![ab]));
+		assert!(!PoeModule::verify_merkle_proof(root, c, 2, vec![ab, a]));
+		assert!(!PoeModule::verify_merkle_proof(root, a, 0, vec![b]));
+	});
+}
+
+#[test]
+fn claims_by_owner_follows_create_transfer_and_revoke() {
+	new_test_ext().execute_with(|| {
+		let claim = BoundedVec::try_from(vec![0, 1, 2, 3]).unwrap();
+		let raw_digest = PoeModule::claim_digest(&claim);
+		let digest = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), digest, None, None));
+
+		let mut claims = PoeModule::claims_of(1);
+		claims.sort();
+		let mut expected = vec![raw_digest, digest];
+		expected.sort();
+		assert_eq!(claims, expected);
+		assert_eq!(PoeModule::owner_claim_count(1), 2);
+
+		assert_ok!(PoeModule::transfer_claim(RuntimeOrigin::signed(1), 2, claim.clone()));
+		assert_eq!(PoeModule::claims_of(1), vec![digest]);
+		assert_eq!(PoeModule::claims_of(2), vec![raw_digest]);
+
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(2), claim));
+		assert_eq!(PoeModule::claims_of(2), vec![]);
+		assert_eq!(PoeModule::owner_claim_count(2), 0);
+		assert!(!OwnerClaimCount::<Test>::contains_key(2));
+	});
+}
+
+#[test]
+fn claims_per_owner_are_bounded() {
+	new_test_ext().execute_with(|| {
+		for i in 1..=3 {
+			assert_ok!(PoeModule::create_digest_claim(
+				RuntimeOrigin::signed(1),
+				H256::repeat_byte(i),
+				None,
+				None
+			));
+		}
+		assert_noop!(
+			PoeModule::create_digest_claim(
+				RuntimeOrigin::signed(1),
+				H256::repeat_byte(4),
+				None,
+				None
+			),
+			Error::<Test>::TooManyClaims
+		);
+
+		assert_ok!(PoeModule::create_digest_claim(
+			RuntimeOrigin::signed(2),
+			H256::repeat_byte(4),
+			None,
+			None
+		));
+		assert_noop!(
+			PoeModule::transfer_digest_claim(RuntimeOrigin::signed(2), 1, H256::repeat_byte(4)),
+			Error::<Test>::TooManyClaims
+		);
+	});
+}
+
+#[test]
+fn migrate_to_v2_indexes_claims_by_owner() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<PoeModule>();
+		let digest = H256::repeat_byte(1);
+		Claims::<Test>::insert(
+			digest,
+			ClaimInfo { owner: 1, block_number: 1, description: None, raw: None },
+		);
+
+		crate::migrations::v2::migrate::<Test>();
+
+		assert_eq!(PoeModule::on_chain_storage_version(), 2);
+		assert_eq!(PoeModule::claims_of(1), vec![digest]);
+		assert_eq!(PoeModule::owner_claim_count(1), 1);
+	});
+}
+
+#[test]
+fn migrate_to_v3_seeds_history() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(2).put::<PoeModule>();
+		let (created, transferred) = (H256::repeat_byte(1), H256::repeat_byte(2));
+		Claims::<Test>::insert(
+			created,
+			ClaimInfo { owner: 3, block_number: 6, description: None, raw: None },
+		);
+		Claims::<Test>::insert(
+			transferred,
+			ClaimInfo { owner: 4, block_number: 7, description: None, raw: None },
+		);
+
+		crate::migrations::v3::migrate::<Test>();
+
+		assert_eq!(PoeModule::on_chain_storage_version(), 3);
+		assert_eq!(
+			PoeModule::claim_history(created),
+			vec![ProvenanceRecord::Created { who: 3, block_number: 6 }]
+		);
+		assert_eq!(
+			PoeModule::claim_history(transferred),
+			vec![ProvenanceRecord::Created { who: 4, block_number: 7 }]
+		);
+	});
+}
+
+#[test]
+fn migrate_to_v3_starts_expiry_cursor_at_current_block() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(2).put::<PoeModule>();
+		ExpiryCursor::<Test>::kill();
+		System::set_block_number(1_000);
+
+		crate::migrations::v3::migrate::<Test>();
+		assert_eq!(ExpiryCursor::<Test>::get(), 1_000);
+
+		assert_eq!(TransferExpiryCursor::<Test>::get(), 1_000);
+
+		// A cursor already draining expiries is left where it is.
+		StorageVersion::new(2).put::<PoeModule>();
+		ExpiryCursor::<Test>::put(5);
+		crate::migrations::v3::migrate::<Test>();
+		assert_eq!(ExpiryCursor::<Test>::get(), 5);
+	});
+}
+
+#[test]
+fn migrate_to_v3_schedules_transfer_proposals() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(2).put::<PoeModule>();
+		let (expired, live) = (H256::repeat_byte(1), H256::repeat_byte(2));
+		PendingTransfers::<Test>::insert(
+			expired,
+			PendingTransfer { from: 1, to: 2, expires_at: 5 },
+		);
+		PendingTransfers::<Test>::insert(live, PendingTransfer { from: 1, to: 2, expires_at: 15 });
+		System::set_block_number(10);
+
+		crate::migrations::v3::migrate::<Test>();
+
+		assert_eq!(PoeModule::pending_transfers(expired), None);
+		assert!(TransferExpiries::<Test>::contains_key(15, live));
+
+		System::set_block_number(15);
+		PoeModule::on_idle(15, Weight::MAX);
+		assert_eq!(PoeModule::pending_transfers(live), None);
+	});
+}
+
+#[test]
+fn propose_and_accept_claim_transfer_works() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), digest, None, None));
+		assert_ok!(PoeModule::propose_claim_transfer(RuntimeOrigin::signed(1), 2, digest));
+		System::assert_last_event(Event::ClaimTransferProposed(1, 2, digest, 11).into());
+
+		// Nothing changes hands until the recipient accepts.
+		assert_eq!(PoeModule::claims(digest).unwrap().owner, 1);
+		assert_ok!(PoeModule::accept_claim_transfer(RuntimeOrigin::signed(2), digest));
+		System::assert_last_event(Event::DigestClaimTransfered(1, 2, digest).into());
+
+		assert_eq!(PoeModule::claims(digest).unwrap().owner, 2);
+		assert_eq!(PoeModule::pending_transfers(digest), None);
+		assert_eq!(Balances::reserved_balance(2), 32 * CLAIM_DEPOSIT);
+	});
+}
+
+#[test]
+fn accept_claim_transfer_checks_recipient_and_expiry() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		assert_noop!(
+			PoeModule::accept_claim_transfer(RuntimeOrigin::signed(2), digest),
+			Error::<Test>::NoPendingTransfer
+		);
+
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), digest, None, None));
+		assert_noop!(
+			PoeModule::propose_claim_transfer(RuntimeOrigin::signed(2), 3, digest),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::propose_claim_transfer(RuntimeOrigin::signed(1), 2, digest));
+		assert_noop!(
+			PoeModule::accept_claim_transfer(RuntimeOrigin::signed(3), digest),
+			Error::<Test>::NotTransferRecipient
+		);
+
+		System::set_block_number(11);
+		assert_noop!(
+			PoeModule::accept_claim_transfer(RuntimeOrigin::signed(2), digest),
+			Error::<Test>::TransferProposalExpired
+		);
+	});
+}
+
+#[test]
+fn cancel_claim_transfer_works() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), digest, None, None));
+		assert_ok!(PoeModule::propose_claim_transfer(RuntimeOrigin::signed(1), 2, digest));
+		assert_noop!(
+			PoeModule::cancel_claim_transfer(RuntimeOrigin::signed(3), digest),
+			Error::<Test>::NoPendingTransfer
+		);
+
+		// The recipient may decline the transfer.
+		assert_ok!(PoeModule::cancel_claim_transfer(RuntimeOrigin::signed(2), digest));
+		System::assert_last_event(Event::ClaimTransferCancelled(2, digest).into());
+		assert_eq!(PoeModule::pending_transfers(digest), None);
+
+		// Revoking the claim drops any proposal.
+		assert_ok!(PoeModule::propose_claim_transfer(RuntimeOrigin::signed(1), 2, digest));
+		assert_ok!(PoeModule::revoke_digest_claim(RuntimeOrigin::signed(1), digest));
+		assert_eq!(PoeModule::pending_transfers(digest), None);
+	});
+}
+
+#[test]
+fn expired_transfer_proposals_are_dropped_in_on_idle() {
+	new_test_ext().execute_with(|| {
+		let (digest, other) = (H256::repeat_byte(1), H256::repeat_byte(2));
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), digest, None, None));
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), other, None, None));
+		assert_ok!(PoeModule::propose_claim_transfer(RuntimeOrigin::signed(1), 2, digest));
+
+		// A new proposal replaces the earlier one along with its expiry.
+		System::set_block_number(2);
+		assert_ok!(PoeModule::propose_claim_transfer(RuntimeOrigin::signed(1), 3, other));
+		assert_ok!(PoeModule::propose_claim_transfer(RuntimeOrigin::signed(1), 3, digest));
+		assert!(!TransferExpiries::<Test>::contains_key(11, digest));
+
+		System::set_block_number(11);
+		PoeModule::on_idle(11, Weight::MAX);
+		assert!(PoeModule::pending_transfers(digest).is_some());
+
+		System::set_block_number(12);
+		PoeModule::on_idle(12, Weight::MAX);
+		assert_eq!(PoeModule::pending_transfers(digest), None);
+		assert_eq!(PoeModule::pending_transfers(other), None);
+		assert_eq!(TransferExpiries::<Test>::iter().count(), 0);
+		assert_eq!(TransferExpiryCursor::<Test>::get(), 13);
+	});
+}
+
+#[test]
+fn direct_transfer_can_be_disabled() {
+	new_test_ext().execute_with(|| {
+		AllowDirectTransfer::set(false);
+		let digest = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), digest, None, None));
+		assert_noop!(
+			PoeModule::transfer_digest_claim(RuntimeOrigin::signed(1), 2, digest),
+			Error::<Test>::DirectTransferDisabled
+		);
+	});
+}
+
+#[test]
+fn cosigned_claim_is_finalized_once_all_signers_endorse() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		let signers = BoundedVec::try_from(vec![2, 3]).unwrap();
+		assert_ok!(PoeModule::create_cosigned_claim(
+			RuntimeOrigin::signed(1),
+			digest,
+			None,
+			signers,
+			10
+		));
+		System::assert_last_event(Event::CoSignedClaimCreated(1, digest, 10).into());
+
+		assert_ok!(PoeModule::endorse_claim(RuntimeOrigin::signed(2), digest));
+		System::assert_last_event(Event::ClaimEndorsed(2, digest).into());
+		let status = PoeModule::endorsement_status(digest).unwrap();
+		assert_eq!(status.endorsed, vec![2]);
+		assert!(!status.finalized);
+
+		assert_ok!(PoeModule::endorse_claim(RuntimeOrigin::signed(3), digest));
+		System::assert_has_event(Event::ClaimEndorsed(3, digest).into());
+		System::assert_last_event(Event::ClaimFinalized(digest).into());
+		assert!(PoeModule::endorsement_status(digest).unwrap().finalized);
+	});
+}
+
+#[test]
+fn endorse_claim_fails_for_invalid_endorsements() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		assert_noop!(
+			PoeModule::create_cosigned_claim(
+				RuntimeOrigin::signed(1),
+				digest,
+				None,
+				BoundedVec::try_from(vec![2, 2]).unwrap(),
+				10
+			),
+			Error::<Test>::DuplicateSigner
+		);
+		assert_noop!(
+			PoeModule::create_cosigned_claim(
+				RuntimeOrigin::signed(1),
+				digest,
+				None,
+				BoundedVec::default(),
+				10
+			),
+			Error::<Test>::NoSigners
+		);
+
+		assert_ok!(PoeModule::create_cosigned_claim(
+			RuntimeOrigin::signed(1),
+			digest,
+			None,
+			BoundedVec::try_from(vec![2, 3]).unwrap(),
+			10
+		));
+		assert_noop!(
+			PoeModule::endorse_claim(RuntimeOrigin::signed(1), digest),
+			Error::<Test>::NotRequiredSigner
+		);
+		assert_ok!(PoeModule::endorse_claim(RuntimeOrigin::signed(2), digest));
+		assert_noop!(
+			PoeModule::endorse_claim(RuntimeOrigin::signed(2), digest),
+			Error::<Test>::AlreadyEndorsed
+		);
+
+		System::set_block_number(10);
+		assert_noop!(
+			PoeModule::endorse_claim(RuntimeOrigin::signed(3), digest),
+			Error::<Test>::EndorsementDeadlinePassed
+		);
+		assert!(!PoeModule::endorsement_status(digest).unwrap().finalized);
+
+		let other = H256::repeat_byte(2);
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), other, None, None));
+		assert_noop!(
+			PoeModule::endorse_claim(RuntimeOrigin::signed(2), other),
+			Error::<Test>::NotCoSignedClaim
+		);
+		assert_eq!(PoeModule::endorsement_status(other), None);
+	});
+}
+
+fn claim_payload(digest: H256, nonce: u64, submission: ClaimSubmission) -> ClaimPayload<Test> {
+	ClaimPayload {
+		digest,
+		description: None,
+		nonce,
+		genesis_hash: PoeModule::genesis_hash(),
+		submission,
+	}
+}
+
+#[test]
+fn create_claim_signed_works() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		let payload = claim_payload(digest, 0, ClaimSubmission::Relayed);
+		let signature = TestSignature(2, payload.encode());
+		assert_ok!(PoeModule::create_claim_signed(RuntimeOrigin::signed(1), payload, 2, signature));
+		System::assert_last_event(Event::SignedClaimCreated(1, 2, digest).into());
+
+		// The signer owns the claim and provides its deposit.
+		assert_eq!(PoeModule::claims(digest).unwrap().owner, 2);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 32 * CLAIM_DEPOSIT);
+		assert_eq!(PoeModule::nonces(2), 1);
+	});
+}
+
+#[test]
+fn create_claim_signed_rejects_bad_signatures_and_replays() {
+	new_test_ext().execute_with(|| {
+		let payload = claim_payload(H256::repeat_byte(1), 0, ClaimSubmission::Relayed);
+		let other_payload = claim_payload(H256::repeat_byte(2), 0, ClaimSubmission::Relayed);
+		assert_noop!(
+			PoeModule::create_claim_signed(
+				RuntimeOrigin::signed(1),
+				payload.clone(),
+				2,
+				TestSignature(3, payload.encode())
+			),
+			Error::<Test>::InvalidSignature
+		);
+		assert_noop!(
+			PoeModule::create_claim_signed(
+				RuntimeOrigin::signed(1),
+				payload.clone(),
+				2,
+				TestSignature(2, other_payload.encode())
+			),
+			Error::<Test>::InvalidSignature
+		);
+
+		// Payloads signed for another chain or for the unsigned path are rejected.
+		let foreign = ClaimPayload { genesis_hash: H256::repeat_byte(9), ..payload.clone() };
+		assert_noop!(
+			PoeModule::create_claim_signed(
+				RuntimeOrigin::signed(1),
+				foreign.clone(),
+				2,
+				TestSignature(2, foreign.encode())
+			),
+			Error::<Test>::WrongGenesis
+		);
+		let unsigned = ClaimPayload { submission: ClaimSubmission::Unsigned, ..payload.clone() };
+		assert_noop!(
+			PoeModule::create_claim_signed(
+				RuntimeOrigin::signed(1),
+				unsigned.clone(),
+				2,
+				TestSignature(2, unsigned.encode())
+			),
+			Error::<Test>::WrongSubmission
+		);
+
+		let signature = TestSignature(2, payload.encode());
+		assert_ok!(PoeModule::create_claim_signed(
+			RuntimeOrigin::signed(1),
+			payload.clone(),
+			2,
+			signature.clone()
+		));
+		assert_ok!(PoeModule::revoke_digest_claim(RuntimeOrigin::signed(2), payload.digest));
+		assert_noop!(
+			PoeModule::create_claim_signed(RuntimeOrigin::signed(3), payload, 2, signature),
+			Error::<Test>::InvalidNonce
+		);
+	});
+}
+
+fn unsigned_claim(digest: H256, signer: u64, nonce: u64) -> crate::Call<Test> {
+	let payload = claim_payload(digest, nonce, ClaimSubmission::Unsigned);
+	let signature = TestSignature(signer, payload.encode());
+	crate::Call::create_claim_unsigned { payload, signer, signature }
+}
+
+#[test]
+fn create_claim_unsigned_works() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		let payload = claim_payload(digest, 0, ClaimSubmission::Unsigned);
+		let signature = TestSignature(2, payload.encode());
+		assert_noop!(
+			PoeModule::create_claim_unsigned(
+				RuntimeOrigin::signed(2),
+				payload.clone(),
+				2,
+				signature.clone()
+			),
+			sp_runtime::traits::BadOrigin
+		);
+
+		assert_ok!(PoeModule::create_claim_unsigned(RuntimeOrigin::none(), payload, 2, signature));
+		System::assert_last_event(Event::DigestClaimCreated(2, digest).into());
+		assert_eq!(PoeModule::claims(digest).unwrap().owner, 2);
+		assert_eq!(PoeModule::nonces(2), 1);
+		assert_eq!(PoeModule::last_unsigned_claim(2), Some(1));
+	});
+}
+
+#[test]
+fn validate_unsigned_checks_signature_and_nonce() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		let valid = PoeModule::validate_unsigned(
+			TransactionSource::External,
+			&unsigned_claim(digest, 2, 0),
+		)
+		.unwrap();
+		assert_eq!(valid.priority, 100);
+		assert_eq!(valid.longevity, 5);
+
+		let payload = claim_payload(digest, 0, ClaimSubmission::Unsigned);
+		let forged = crate::Call::create_claim_unsigned {
+			payload: payload.clone(),
+			signer: 2,
+			signature: TestSignature(3, payload.encode()),
+		};
+		assert_eq!(
+			PoeModule::validate_unsigned(TransactionSource::External, &forged),
+			InvalidTransaction::BadProof.into()
+		);
+		let relayed = claim_payload(digest, 0, ClaimSubmission::Relayed);
+		let relayed = crate::Call::create_claim_unsigned {
+			payload: relayed.clone(),
+			signer: 2,
+			signature: TestSignature(2, relayed.encode()),
+		};
+		assert_eq!(
+			PoeModule::validate_unsigned(TransactionSource::External, &relayed),
+			InvalidTransaction::BadProof.into()
+		);
+		assert_eq!(
+			PoeModule::validate_unsigned(
+				TransactionSource::External,
+				&unsigned_claim(digest, 2, 1)
+			),
+			InvalidTransaction::Future.into()
+		);
+
+		Nonces::<Test>::insert(2, 1);
+		assert_eq!(
+			PoeModule::validate_unsigned(
+				TransactionSource::External,
+				&unsigned_claim(digest, 2, 0)
+			),
+			InvalidTransaction::Stale.into()
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_rejects_claims_dispatch_would_fail_on() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), digest, None, None));
+		assert_eq!(
+			PoeModule::validate_unsigned(
+				TransactionSource::External,
+				&unsigned_claim(digest, 2, 0)
+			),
+			InvalidTransaction::Custom(UNSIGNED_CLAIM_EXISTS).into()
+		);
+
+		// Account 4 has no funds to reserve the deposit from.
+		assert_eq!(
+			PoeModule::validate_unsigned(
+				TransactionSource::External,
+				&unsigned_claim(H256::repeat_byte(2), 4, 0)
+			),
+			InvalidTransaction::Payment.into()
+		);
+
+		for byte in 2..5 {
+			let digest = H256::repeat_byte(byte);
+			assert_ok!(PoeModule::create_digest_claim(
+				RuntimeOrigin::signed(2),
+				digest,
+				None,
+				None
+			));
+		}
+		assert_eq!(
+			PoeModule::validate_unsigned(
+				TransactionSource::External,
+				&unsigned_claim(H256::repeat_byte(5), 2, 0)
+			),
+			InvalidTransaction::Custom(UNSIGNED_TOO_MANY_CLAIMS).into()
+		);
+	});
+}
+
+#[test]
+fn unsigned_claims_are_rate_limited() {
+	new_test_ext().execute_with(|| {
+		let first = H256::repeat_byte(1);
+		let payload = claim_payload(first, 0, ClaimSubmission::Unsigned);
+		let signature = TestSignature(2, payload.encode());
+		assert_ok!(PoeModule::create_claim_unsigned(RuntimeOrigin::none(), payload, 2, signature));
+
+		let second = unsigned_claim(H256::repeat_byte(2), 2, 1);
+		assert_eq!(
+			PoeModule::validate_unsigned(TransactionSource::External, &second),
+			InvalidTransaction::Custom(UNSIGNED_RATE_LIMITED).into()
+		);
+		let payload = claim_payload(H256::repeat_byte(2), 1, ClaimSubmission::Unsigned);
+		let signature = TestSignature(2, payload.encode());
+		assert_noop!(
+			PoeModule::create_claim_unsigned(
+				RuntimeOrigin::none(),
+				payload.clone(),
+				2,
+				signature.clone()
+			),
+			Error::<Test>::UnsignedRateLimited
+		);
+
+		// Other accounts are not affected.
+		assert_ok!(PoeModule::validate_unsigned(
+			TransactionSource::External,
+			&unsigned_claim(H256::repeat_byte(3), 3, 0)
+		));
+
+		System::set_block_number(6);
+		assert_ok!(PoeModule::validate_unsigned(TransactionSource::External, &second));
+		assert_ok!(PoeModule::create_claim_unsigned(RuntimeOrigin::none(), payload, 2, signature));
+	});
+}
+
+#[test]
+fn force_revoke_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = BoundedVec::try_from(vec![0, 1, 2, 3]).unwrap();
+		let digest = PoeModule::claim_digest(&claim);
+		let reason: BoundedVec<u8, _> = BoundedVec::try_from(b"illegal".to_vec()).unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_noop!(
+			PoeModule::force_revoke_claim(RuntimeOrigin::signed(1), digest, reason.clone()),
+			sp_runtime::traits::BadOrigin
+		);
+
+		assert_ok!(PoeModule::force_revoke_claim(RuntimeOrigin::root(), digest, reason.clone()));
+		System::assert_last_event(Event::ClaimForceRevoked(1, digest, reason).into());
+		assert_eq!(PoeModule::claims(digest), None);
+		assert_eq!(Proofs::<Test>::get(&claim), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(
+			PoeModule::claim_history(digest).last(),
+			Some(&ProvenanceRecord::ForceRevoked { block_number: 1 })
+		);
+	});
+}
+
+#[test]
+fn force_transfer_claim_works() {
+	new_test_ext().execute_with(|| {
+		let digest = H256::repeat_byte(1);
+		let reason: BoundedVec<u8, _> = BoundedVec::try_from(b"court order".to_vec()).unwrap();
+		assert_noop!(
+			PoeModule::force_transfer_claim(RuntimeOrigin::root(), 2, digest, reason.clone()),
+			Error::<Test>::ClaimNotExist
+		);
+
+		assert_ok!(PoeModule::create_digest_claim(RuntimeOrigin::signed(1), digest, None, None));
+		assert_ok!(PoeModule::force_transfer_claim(
+			RuntimeOrigin::root(),
+			2,
+			digest,
+			reason.clone()
+		));
+		System::assert_last_event(Event::ClaimForceTransferred(1, 2, digest, reason).into());
+		assert_eq!(PoeModule::claims(digest).unwrap().owner, 2);
+		assert_eq!(Balances::reserved_balance(2), 32 * CLAIM_DEPOSIT);
+	});
+}
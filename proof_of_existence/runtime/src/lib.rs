@@ -15,7 +15,7 @@ use sp_runtime::{
 	traits::{
 		AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One, Verify,
 	},
-	transaction_validity::{TransactionSource, TransactionValidity},
+	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, MultiSignature,
 };
 use sp_std::prelude::*;
@@ -27,7 +27,8 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo,
+		ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, Contains, KeyOwnerProofSystem,
+		Randomness, StorageInfo,
 	},
 	weights::{
 		constants::{
@@ -39,6 +40,7 @@ pub use frame_support::{
 };
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
+pub use pallet_poe::Call as PoeCall;
 pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
 #[cfg(any(feature = "std", test))]
@@ -103,10 +105,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 106,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 4,
 	state_version: 1,
 };
 
@@ -153,7 +155,7 @@ parameter_types! {
 
 impl frame_system::Config for Runtime {
 	/// The basic call filter to use in dispatchable.
-	type BaseCallFilter = frame_support::traits::Everything;
+	type BaseCallFilter = TxPause;
 	/// Block & extrinsics weights: base values and limits.
 	type BlockWeights = BlockWeights;
 	/// The maximum length of a block (in bytes).
@@ -267,10 +269,48 @@ impl pallet_sudo::Config for Runtime {
 	type RuntimeCall = RuntimeCall;
 }
 
+parameter_types! {
+	pub const MaxClaimLength: u32 = 10;
+	pub const ClaimDeposit: Balance = EXISTENTIAL_DEPOSIT;
+	pub const PoeUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+}
+
 /// Configure the pallet-template in pallets/template.
 impl pallet_poe::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type MaxClaimLength = ConstU32<10>;
+	type MaxClaimLength = MaxClaimLength;
+	type MaxDescriptionLength = ConstU32<256>;
+	type Currency = Balances;
+	type ClaimDeposit = ClaimDeposit;
+	type MaxHistoryLength = ConstU32<64>;
+	type MaxExpiriesPerBlock = ConstU32<256>;
+	type MaxBatchSize = ConstU32<128>;
+	type MaxClaimsPerOwner = ConstU32<1024>;
+	type AllowDirectTransfer = ConstBool<false>;
+	type TransferProposalPeriod = ConstU32<DAYS>;
+	type MaxSigners = ConstU32<16>;
+	type OffchainSignature = Signature;
+	type OffchainPublic = <Signature as Verify>::Signer;
+	type UnsignedPriority = PoeUnsignedPriority;
+	type UnsignedInterval = ConstU32<MINUTES>;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxReasonLength = ConstU32<256>;
+}
+
+/// Calls `TxPause` can never pause: those of `Sudo`, so root can always recover, and the
+/// `Timestamp` inherent, without which no block can be authored.
+pub struct UnpausableCalls;
+impl Contains<pallet_tx_pause::RuntimeCallNameOf<Runtime>> for UnpausableCalls {
+	fn contains(full_name: &pallet_tx_pause::RuntimeCallNameOf<Runtime>) -> bool {
+		matches!(full_name.0.as_slice(), b"Sudo" | b"Timestamp")
+	}
+}
+
+impl pallet_tx_pause::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type WhitelistedCalls = UnpausableCalls;
+	type MaxNameLen = ConstU32<256>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -290,6 +330,7 @@ construct_runtime!(
 		Sudo: pallet_sudo,
 		// Include the custom logic from the pallet-template in the runtime.
 		PoeModule: pallet_poe,
+		TxPause: pallet_tx_pause,
 	}
 );
 
@@ -508,6 +549,28 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_poe_runtime_api::PoeApi<Block, AccountId, BlockNumber, Hash> for Runtime {
+		fn claim_history(
+			digest: Hash,
+		) -> Vec<pallet_poe_runtime_api::ProvenanceRecord<AccountId, BlockNumber>> {
+			PoeModule::claim_history(digest)
+		}
+
+		fn verify_merkle_proof(root: Hash, leaf: Hash, index: u32, proof: Vec<Hash>) -> bool {
+			PoeModule::verify_merkle_proof(root, leaf, index, proof)
+		}
+
+		fn claims_of(account: AccountId) -> Vec<Hash> {
+			PoeModule::claims_of(account)
+		}
+
+		fn endorsement_status(
+			digest: Hash,
+		) -> Option<pallet_poe_runtime_api::EndorsementStatus<AccountId, BlockNumber>> {
+			PoeModule::endorsement_status(digest)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -575,7 +638,7 @@ impl_runtime_apis! {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use frame_support::traits::WhitelistedStorageKeys;
+	use frame_support::{assert_noop, assert_ok, traits::WhitelistedStorageKeys};
 	use sp_core::hexdisplay::HexDisplay;
 	use std::collections::HashSet;
 
@@ -607,4 +670,55 @@ mod tests {
 			whitelist.contains("26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7")
 		);
 	}
+
+	fn full_name(
+		pallet_name: &[u8],
+		call_name: &[u8],
+	) -> pallet_tx_pause::RuntimeCallNameOf<Runtime> {
+		(pallet_name.to_vec().try_into().unwrap(), call_name.to_vec().try_into().unwrap())
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap()
+			.into()
+	}
+
+	#[test]
+	fn sudo_and_tx_pause_cannot_be_paused() {
+		new_test_ext().execute_with(|| {
+			for name in [
+				full_name(b"Sudo", b"sudo"),
+				full_name(b"Sudo", b"set_key"),
+				full_name(b"TxPause", b"pause"),
+				full_name(b"TxPause", b"unpause"),
+				full_name(b"Timestamp", b"set"),
+			] {
+				assert_noop!(
+					TxPause::pause(RuntimeOrigin::root(), name),
+					pallet_tx_pause::Error::<Runtime>::Unpausable
+				);
+			}
+		});
+	}
+
+	#[test]
+	fn paused_calls_are_filtered() {
+		new_test_ext().execute_with(|| {
+			let call = RuntimeCall::PoeModule(pallet_poe::Call::revoke_digest_claim {
+				digest: Default::default(),
+			});
+			assert!(<Runtime as frame_system::Config>::BaseCallFilter::contains(&call));
+
+			assert_ok!(TxPause::pause(
+				RuntimeOrigin::root(),
+				full_name(b"PoeModule", b"revoke_digest_claim")
+			));
+			assert!(!<Runtime as frame_system::Config>::BaseCallFilter::contains(&call));
+
+			let sudo = RuntimeCall::Sudo(pallet_sudo::Call::sudo { call: Box::new(call.clone()) });
+			assert!(<Runtime as frame_system::Config>::BaseCallFilter::contains(&sudo));
+		});
+	}
 }